@@ -0,0 +1,54 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokens::*;
+
+fn notify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("notify");
+
+    for count in [1usize, 10, 100] {
+        let counter = Arc::new(AtomicUsize::default());
+        let token = DefaultChangeToken::new();
+        let _registrations: Vec<_> = (0..count)
+            .map(|_| {
+                token.register(
+                    Box::new(|state| {
+                        state
+                            .unwrap()
+                            .downcast_ref::<AtomicUsize>()
+                            .unwrap()
+                            .fetch_add(1, Ordering::Relaxed);
+                    }),
+                    Some(counter.clone()),
+                )
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &token, |b, token| {
+            b.iter(|| token.notify())
+        });
+    }
+
+    group.finish();
+}
+
+fn churn(c: &mut Criterion) {
+    let token = DefaultChangeToken::new();
+    let _registrations: Vec<_> = (0..100)
+        .map(|_| token.register(Box::new(|_| {}), None))
+        .collect();
+
+    c.bench_function("register and unregister", |b| {
+        b.iter(|| black_box(token.register(Box::new(|_| {}), None)))
+    });
+
+    c.bench_function("register, unregister, and notify", |b| {
+        b.iter(|| {
+            drop(token.register(Box::new(|_| {}), None));
+            token.notify()
+        })
+    });
+}
+
+criterion_group!(benches, notify, churn);
+criterion_main!(benches);
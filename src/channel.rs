@@ -0,0 +1,244 @@
+use crate::{Callback, ChangeToken, DefaultChangeToken, Registration};
+use std::{
+    any::Any,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+/// Represents the message sent to a channel when a [`ChangeToken`](crate::ChangeToken) changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChangeEvent {
+    sequence: u64,
+}
+
+impl ChangeEvent {
+    /// Gets the number of changes observed by the channel, including this change.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+/// Represents a channel receiver that receives a [`ChangeEvent`] whenever a [`ChangeToken`](crate::ChangeToken) changes.
+///
+/// # Remarks
+///
+/// The receiver owns the change token and its registration. It dereferences to the
+/// underlying receiver so it can be used anywhere the receiver is expected.
+pub struct ChangeReceiver<R = Receiver<ChangeEvent>> {
+    receiver: R,
+    _registration: Registration,
+    _token: Box<dyn ChangeToken>,
+}
+
+impl<R> ChangeReceiver<R> {
+    fn new<T, S>(token: T, sender: S, receiver: R) -> Self
+    where
+        T: ChangeToken + 'static,
+        S: Fn(ChangeEvent) + Send + Sync + 'static,
+    {
+        let sequence = AtomicU64::default();
+        let registration = token.register_or_invoke(
+            Box::new(move |_| {
+                sender(ChangeEvent {
+                    sequence: sequence.fetch_add(1, Ordering::SeqCst) + 1,
+                })
+            }),
+            None,
+        );
+
+        Self {
+            receiver,
+            _registration: registration,
+            _token: Box::new(token),
+        }
+    }
+}
+
+impl<R> Deref for ChangeReceiver<R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+/// Defines the behavior to convert a [`ChangeToken`](crate::ChangeToken) into a channel receiver.
+///
+/// # Remarks
+///
+/// A token that [must be polled](crate::ChangeToken::must_poll) does not invoke callbacks
+/// and, therefore, never sends a [`ChangeEvent`].
+pub trait IntoReceiver: ChangeToken + Sized + 'static {
+    /// Converts the change token into a [receiver](std::sync::mpsc::Receiver) that receives a [`ChangeEvent`] on each change.
+    fn into_receiver(self) -> ChangeReceiver {
+        let (sender, receiver) = channel();
+        ChangeReceiver::new(
+            self,
+            move |event| sender.send(event).unwrap_or_default(),
+            receiver,
+        )
+    }
+
+    /// Converts the change token into a [crossbeam receiver](crossbeam_channel::Receiver) that receives a [`ChangeEvent`]
+    /// on each change.
+    #[cfg(feature = "crossbeam")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crossbeam")))]
+    fn into_crossbeam_receiver(self) -> ChangeReceiver<crossbeam_channel::Receiver<ChangeEvent>> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        ChangeReceiver::new(
+            self,
+            move |event| sender.send(event).unwrap_or_default(),
+            receiver,
+        )
+    }
+}
+
+impl<T: ChangeToken + 'static> IntoReceiver for T {}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes whenever a message arrives on a channel.
+///
+/// # Remarks
+///
+/// The messages are received and discarded by a dedicated thread, which invokes the registered
+/// callbacks. The thread exits when the channel is disconnected or when a message arrives after
+/// the token has been dropped.
+pub struct ChannelChangeToken {
+    inner: Arc<DefaultChangeToken>,
+}
+
+impl ChannelChangeToken {
+    /// Initializes a new channel change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - The [receiver](std::sync::mpsc::Receiver) to observe
+    pub fn new<T: Send + 'static>(receiver: Receiver<T>) -> Self {
+        Self::spawn(move || receiver.recv().is_ok())
+    }
+
+    /// Initializes a new channel change token for a crossbeam channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - The [crossbeam receiver](crossbeam_channel::Receiver) to observe
+    #[cfg(feature = "crossbeam")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crossbeam")))]
+    pub fn crossbeam<T: Send + 'static>(receiver: crossbeam_channel::Receiver<T>) -> Self {
+        Self::spawn(move || receiver.recv().is_ok())
+    }
+
+    fn spawn<F: FnMut() -> bool + Send + 'static>(mut received: F) -> Self {
+        let inner = Arc::new(DefaultChangeToken::new());
+        let token = Arc::downgrade(&inner);
+
+        thread::spawn(move || {
+            while received() {
+                match token.upgrade() {
+                    // a callback that panics must not end the thread
+                    Some(token) => token.try_notify().unwrap_or_default(),
+                    _ => break,
+                }
+            }
+        });
+
+        Self { inner }
+    }
+}
+
+impl ChangeToken for ChannelChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{SharedChangeToken, SingleChangeToken};
+    use std::{sync::mpsc::TryRecvError, time::Duration};
+
+    #[test]
+    fn receiver_should_receive_event_on_each_change() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let receiver = token.clone().into_receiver();
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        assert_eq!(receiver.try_recv().map(|e| e.sequence()), Ok(1));
+        assert_eq!(receiver.try_recv().map(|e| e.sequence()), Ok(2));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn receiver_should_receive_event_when_single_token_has_changed() {
+        // arrange
+        let token = SingleChangeToken::new();
+
+        token.notify();
+
+        // act
+        let receiver = token.into_receiver();
+
+        // assert
+        assert_eq!(receiver.try_recv().map(|e| e.sequence()), Ok(1));
+    }
+
+    #[test]
+    #[cfg(feature = "crossbeam")]
+    fn crossbeam_receiver_should_receive_event_on_change() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let receiver = token.clone().into_crossbeam_receiver();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(receiver.try_recv().map(|e| e.sequence()), Ok(1));
+    }
+
+    #[test]
+    fn channel_change_token_should_change_when_message_arrives() {
+        // arrange
+        let (sender, receiver) = channel();
+        let changed = ChannelChangeToken::new(receiver).into_receiver();
+
+        // act
+        sender.send("change").unwrap();
+
+        // assert
+        assert!(changed.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "crossbeam")]
+    fn crossbeam_channel_change_token_should_change_when_message_arrives() {
+        // arrange
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let changed = ChannelChangeToken::crossbeam(receiver).into_receiver();
+
+        // act
+        sender.send(42).unwrap();
+
+        // assert
+        assert!(changed.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
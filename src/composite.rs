@@ -1,4 +1,6 @@
-use crate::{Callback, ChangeToken, Registration, SharedChangeToken, SingleChangeToken};
+use crate::{
+    Callback, ChangeToken, NotifyError, Registration, SharedChangeToken, SingleChangeToken,
+};
 use std::{
     any::Any,
     sync::{Arc, Weak},
@@ -75,6 +77,15 @@ impl CompositeChangeToken {
     pub fn notify(&self) {
         self.inner.notify()
     }
+
+    /// Notifies any registered callbacks of a change.
+    ///
+    /// # Returns
+    ///
+    /// An [error](crate::NotifyError) containing the panics of any callbacks that panicked.
+    pub fn try_notify(&self) -> Result<(), NotifyError> {
+        self.inner.try_notify()
+    }
 }
 
 impl ChangeToken for CompositeChangeToken {
@@ -86,9 +97,24 @@ impl ChangeToken for CompositeChangeToken {
         self.mediator.children.iter().all(|t| t.must_poll())
     }
 
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
         self.inner.register(callback, state)
     }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        // a child may have changed before it was composed or may require polling, in
+        // which case the change never propagated. the composite changes at most once so
+        // propagate it now. any panics belong to the callbacks of other consumers
+        if !self.inner.changed() && self.mediator.children.iter().any(|t| t.changed()) {
+            self.inner.try_notify().ok();
+        }
+
+        self.inner.register_or_invoke(callback, state)
+    }
 }
 
 #[cfg(test)]
@@ -256,4 +282,32 @@ mod tests {
         // assert
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn register_or_invoke_should_invoke_callback_if_child_has_changed() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let child = SingleChangeToken::new();
+
+        child.notify();
+
+        let child: Box<dyn ChangeToken> = Box::new(child);
+        let tokens = vec![child];
+        let token = CompositeChangeToken::new(tokens.into_iter());
+
+        // act
+        let _registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
 }
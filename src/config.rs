@@ -0,0 +1,308 @@
+use crate::{ChangeToken, FileChangeToken, NeverChangeToken, Watched};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Represents the possible errors that can occur when loading a configuration file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Indicates the file could not be read.
+    Io(io::Error),
+
+    /// Indicates the content of the file could not be parsed or is invalid.
+    Parse(Box<dyn Error + Send + Sync>),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::Io(error) => write!(f, "the configuration file could not be read: {}", error),
+            Self::Parse(error) => write!(f, "the configuration file is invalid: {}", error),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl<T: Send + Sync + 'static> Watched<T> {
+    /// Initializes a new watched value loaded from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the file to load and watch for changes
+    /// * `parse` - The function that parses and validates the content of the file
+    /// * `on_error` - The function that is called when reloading the file fails
+    ///
+    /// # Remarks
+    ///
+    /// If the file cannot be loaded initially, the error is returned. When the file changes,
+    /// each new version is parsed before it replaces the current value. If reading or parsing
+    /// a new version fails, the error is provided to `on_error` and the last good value is retained.
+    /// The file may be removed and recreated; the value is reloaded once the file is recreated.
+    pub fn file<TPath, TParser, THandler>(
+        path: TPath,
+        parse: TParser,
+        on_error: THandler,
+    ) -> Result<Self, ConfigError>
+    where
+        TPath: AsRef<Path>,
+        TParser: Fn(&str) -> Result<T, Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+        THandler: Fn(ConfigError) + Send + Sync + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let file = path.clone();
+        let on_error = Arc::new(on_error);
+        let handler = on_error.clone();
+
+        // the directory containing the file is watched so that the file can be removed and
+        // recreated without ending the subscription. if the directory cannot be watched,
+        // the error is reported and the value is no longer reloaded
+        let producer = move || -> Box<dyn ChangeToken> {
+            match FileChangeToken::entry(&path) {
+                Ok(token) => Box::new(token),
+                Err(error) => {
+                    handler(ConfigError::Io(io::Error::other(error)));
+                    Box::new(NeverChangeToken::new())
+                }
+            }
+        };
+
+        Self::try_new(
+            move || parse(&fs::read_to_string(&file)?).map_err(ConfigError::Parse),
+            producer,
+            move |error| on_error(error),
+        )
+    }
+
+    /// Initializes a new watched value loaded from a JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the JSON file to load and watch for changes
+    /// * `on_error` - The function that is called when reloading the file fails
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`Watched::file`] for the reload semantics.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json<TPath, THandler>(path: TPath, on_error: THandler) -> Result<Self, ConfigError>
+    where
+        T: serde::de::DeserializeOwned,
+        TPath: AsRef<Path>,
+        THandler: Fn(ConfigError) + Send + Sync + 'static,
+    {
+        Self::file(path, |content| Ok(serde_json::from_str(content)?), on_error)
+    }
+
+    /// Initializes a new watched value loaded from a TOML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the TOML file to load and watch for changes
+    /// * `on_error` - The function that is called when reloading the file fails
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`Watched::file`] for the reload semantics.
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn toml<TPath, THandler>(path: TPath, on_error: THandler) -> Result<Self, ConfigError>
+    where
+        T: serde::de::DeserializeOwned,
+        TPath: AsRef<Path>,
+        THandler: Fn(ConfigError) + Send + Sync + 'static,
+    {
+        Self::file(path, |content| Ok(toml::from_str(content)?), on_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Registration;
+    use serde::Deserialize;
+    use std::env::temp_dir;
+    use std::fs::{remove_file, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::time::{Duration, Instant};
+
+    #[derive(Deserialize)]
+    struct Settings {
+        value: u8,
+    }
+
+    // overwrite the file in place with a single write so that
+    // exactly one modification is observed by the watcher
+    fn overwrite(path: &PathBuf, content: &str) {
+        let mut file = OpenOptions::new().write(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    // the file might be reloaded more than once, so wait for the expected value
+    fn received(receiver: &Receiver<u8>, expected: u8) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match receiver.recv_timeout(timeout) {
+                Ok(value) if value == expected => return true,
+                Ok(_) => continue,
+                Err(_) => break,
+            }
+        }
+
+        false
+    }
+
+    fn subscribe(settings: &Watched<Settings>) -> (Receiver<u8>, Registration) {
+        let (sender, receiver) = channel();
+        let registration =
+            settings.subscribe(move |_, new| sender.send(new.value).unwrap_or_default());
+        (receiver, registration)
+    }
+
+    #[test]
+    fn json_should_load_initial_value() {
+        // arrange
+        let path = temp_dir().join("config.1.json");
+
+        fs::write(&path, r#"{"value":1}"#).unwrap();
+
+        // act
+        let settings = Watched::<Settings>::json(&path, |_| {}).unwrap();
+
+        // assert
+        remove_file(&path).ok();
+        assert_eq!(settings.get().value, 1);
+    }
+
+    #[test]
+    fn json_should_return_error_when_file_is_invalid() {
+        // arrange
+        let path = temp_dir().join("config.2.json");
+
+        fs::write(&path, r#"{"value":"one"}"#).unwrap();
+
+        // act
+        let result = Watched::<Settings>::json(&path, |_| {});
+
+        // assert
+        remove_file(&path).ok();
+        assert!(matches!(result, Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn json_should_reload_when_file_changes() {
+        // arrange
+        let path = temp_dir().join("config.3.json");
+
+        fs::write(&path, r#"{"value":1}"#).unwrap();
+
+        let settings = Watched::<Settings>::json(&path, |_| {}).unwrap();
+        let (reloaded, _registration) = subscribe(&settings);
+
+        // act
+        overwrite(&path, r#"{"value":2}"#);
+
+        // assert
+        let reloaded = received(&reloaded, 2);
+        remove_file(&path).ok();
+        assert!(reloaded);
+        assert_eq!(settings.get().value, 2);
+    }
+
+    #[test]
+    fn json_should_retain_last_good_value_when_file_becomes_invalid() {
+        // arrange
+        let path = temp_dir().join("config.4.json");
+
+        fs::write(&path, r#"{"value":1}"#).unwrap();
+
+        let (sender, failed) = channel();
+        let settings = Watched::<Settings>::json(&path, move |error| {
+            if matches!(error, ConfigError::Parse(_)) {
+                sender.send(()).unwrap_or_default();
+            }
+        })
+        .unwrap();
+
+        // act
+        overwrite(&path, r#"{"value":x}"#);
+
+        // assert
+        let failed = failed.recv_timeout(Duration::from_secs(5));
+        remove_file(&path).ok();
+        assert_eq!(failed, Ok(()));
+        assert_eq!(settings.get().value, 1);
+    }
+
+    #[test]
+    fn json_should_reload_when_file_is_removed_and_recreated() {
+        // arrange
+        let path = temp_dir().join("config.6.json");
+
+        fs::write(&path, r#"{"value":1}"#).unwrap();
+
+        let (sender, failed) = channel();
+        let settings = Watched::<Settings>::json(&path, move |error| {
+            if matches!(error, ConfigError::Io(_)) {
+                sender.send(()).unwrap_or_default();
+            }
+        })
+        .unwrap();
+        let (reloaded, _registration) = subscribe(&settings);
+
+        remove_file(&path).unwrap();
+
+        let failed = failed.recv_timeout(Duration::from_secs(5));
+
+        // act
+        fs::write(&path, r#"{"value":2}"#).unwrap();
+
+        // assert
+        let reloaded = received(&reloaded, 2);
+        remove_file(&path).ok();
+        assert_eq!(failed, Ok(()));
+        assert!(reloaded);
+        assert_eq!(settings.get().value, 2);
+    }
+
+    #[test]
+    fn toml_should_reload_when_file_changes() {
+        // arrange
+        let path = temp_dir().join("config.5.toml");
+
+        fs::write(&path, "value = 1").unwrap();
+
+        let settings = Watched::<Settings>::toml(&path, |_| {}).unwrap();
+        let (reloaded, _registration) = subscribe(&settings);
+
+        // act
+        overwrite(&path, "value = 2");
+
+        // assert
+        let reloaded = received(&reloaded, 2);
+        remove_file(&path).ok();
+        assert!(reloaded);
+        assert_eq!(settings.get().value, 2);
+    }
+}
@@ -1,29 +1,260 @@
-use crate::{Callback, ChangeToken, Registration};
+use crate::{
+    sync::{MutexExt, RwLockExt},
+    Callback, ChangeToken, Changed, Dispatcher, Key, Registration, Release, Slab, Slot,
+    VersionedCallback,
+};
+use arc_swap::ArcSwap;
+
+#[cfg(feature = "futures")]
+use crate::Changes;
 use std::{
     any::Any,
+    cmp::Reverse,
+    error::Error,
+    fmt::{Debug, Display, Formatter, Result as FormatResult},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, RwLock, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, RwLock, Weak,
     },
+    task::{Context, Poll, Waker},
 };
 
+type Handler = dyn Fn(u64) + Send + Sync;
+
+#[derive(Clone)]
+struct Entry {
+    slot: Weak<Slot<Handler>>,
+    priority: i32,
+    sequence: u64,
+}
+
+// represents the state provided when a callback is registered. the state is captured by
+// the callback in the slot owned by the registration, so it's dropped with the registration.
+// a token that notifies from its own thread already provides the state to its callbacks on
+// that thread. the state is only ever provided to the callback it was registered with, so
+// a dispatcher invoking the callback on another thread is no different
+struct State(Option<Arc<dyn Any>>);
+
+unsafe impl Send for State {}
+unsafe impl Sync for State {}
+
+impl State {
+    fn get(&self) -> Option<Arc<dyn Any>> {
+        self.0.clone()
+    }
+}
+
+// represents a callback invocation handed to a dispatcher. the slot is checked
+// when the work is executed so an unregistered callback is never invoked
+struct Invocation {
+    slot: Arc<Slot<Handler>>,
+    version: u64,
+}
+
+impl Invocation {
+    fn invoke(self) {
+        if let Some(callback) = self.slot.fire() {
+            callback(self.version)
+        }
+    }
+}
+
+// registrations release their entry as soon as they are dropped, which
+// keeps registration and unregistration O(1) regardless of churn.
+//
+// notifications never acquire the lock. instead, they read an immutable snapshot
+// of the entries that is only rebuilt when the entries have changed since the
+// last notification. in the common case, a notification is lock and allocation free.
+//
+// the slab reuses vacant entries so its order is meaningless. the snapshot is sorted
+// by priority and then by a sequence number assigned when the callback is registered.
+//
+// the wakers of pending futures and streams are kept alongside the entries. a waker is
+// only woken once; the future or stream parks a new waker each time it is polled.
+#[derive(Default)]
+struct Callbacks {
+    entries: RwLock<Slab<Entry>>,
+    snapshot: ArcSwap<Vec<Entry>>,
+    dirty: AtomicBool,
+    sequence: AtomicU64,
+    wakers: Mutex<Slab<Waker>>,
+    waiting: AtomicBool,
+}
+
+impl Callbacks {
+    fn insert(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+        once: bool,
+        owner: Weak<dyn Release>,
+    ) -> Arc<Slot<Handler>> {
+        let state = State(state);
+        let handler: Box<Handler> = Box::new(move |version| callback(version, state.get()));
+        let mut entries = self.entries.write_or_recover();
+        let key = entries.vacant_key();
+        let slot = Arc::new(Slot::owned(handler, owner, key, once));
+
+        entries.insert(Entry {
+            slot: Arc::downgrade(&slot),
+            priority,
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+        });
+        self.dirty.store(true, Ordering::SeqCst);
+        slot
+    }
+
+    fn clear(&self) {
+        let entries = {
+            let mut entries = self.entries.write_or_recover();
+            self.dirty.store(true, Ordering::SeqCst);
+            entries.drain()
+        };
+
+        for entry in entries {
+            if let Some(slot) = entry.slot.upgrade() {
+                slot.detach();
+            }
+        }
+    }
+
+    fn snapshot(&self) -> Arc<Vec<Entry>> {
+        if self.dirty.swap(false, Ordering::SeqCst) {
+            // the read-lock guarantees that every completed registration is captured.
+            // a registration that races with this rebuild marks the snapshot dirty again
+            let mut snapshot: Vec<_> = self.entries.read_or_recover().values().cloned().collect();
+
+            snapshot.sort_unstable_by_key(|e| (Reverse(e.priority), e.sequence));
+
+            // the rebuilt snapshot is returned rather than loaded again so that
+            // a snapshot stored by another thread is never observed instead
+            let snapshot = Arc::new(snapshot);
+            self.snapshot.store(snapshot.clone());
+            snapshot
+        } else {
+            self.snapshot.load_full()
+        }
+    }
+
+    fn park(&self, key: Option<Key>, waker: &Waker) -> Key {
+        let mut wakers = self.wakers.lock_or_recover();
+        let key = match key.and_then(|key| wakers.get_mut(key).map(|parked| (key, parked))) {
+            Some((key, parked)) => {
+                if !parked.will_wake(waker) {
+                    *parked = waker.clone();
+                }
+                key
+            }
+            _ => wakers.insert(waker.clone()),
+        };
+
+        self.waiting.store(true, Ordering::SeqCst);
+        key
+    }
+
+    fn unpark(&self, key: Key) {
+        let waker = self.wakers.lock_or_recover().remove(key);
+        drop(waker);
+    }
+
+    fn wake(&self) {
+        // the lock is only acquired when a waker has been parked. a waker parked after
+        // this check always observes the version that caused the notification
+        if self.waiting.swap(false, Ordering::SeqCst) {
+            let wakers = self.wakers.lock_or_recover().drain();
+
+            for waker in wakers {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Release for Callbacks {
+    fn release(&self, key: Key) {
+        // the snapshot only holds a weak reference to the slot, which owns the
+        // callback and its state, so it's only marked to be rebuilt
+        let mut entries = self.entries.write_or_recover();
+        entries.remove(key);
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Callbacks {
+    fn drop(&mut self) {
+        // any outstanding registrations are no longer active
+        for entry in self.entries.get_mut_or_recover().values() {
+            if let Some(slot) = entry.slot.upgrade() {
+                slot.detach();
+            }
+        }
+    }
+}
+
+/// Represents the panics that occurred while invoking [`ChangeToken`](crate::ChangeToken) callbacks.
+pub struct NotifyError(Vec<Box<dyn Any + Send>>);
+
+impl NotifyError {
+    /// Gets the payloads of the panics that occurred, in the order the callbacks were invoked.
+    pub fn panics(&self) -> &[Box<dyn Any + Send>] {
+        &self.0
+    }
+
+    /// Consumes the error and returns the payloads of the panics that occurred.
+    pub fn into_panics(self) -> Vec<Box<dyn Any + Send>> {
+        self.0
+    }
+
+    fn message(payload: &(dyn Any + Send)) -> &str {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message
+        } else {
+            "Box<dyn Any>"
+        }
+    }
+}
+
+impl Debug for NotifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.debug_list()
+            .entries(self.0.iter().map(|p| Self::message(p.as_ref())))
+            .finish()
+    }
+}
+
+impl Display for NotifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{} change token callback(s) panicked", self.0.len())?;
+
+        if let Some(payload) = self.0.first() {
+            write!(f, ": {}", Self::message(payload.as_ref()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for NotifyError {}
+
 /// Represents a default [`ChangeToken`](crate::ChangeToken) that may change zero or more times.
 #[derive(Default)]
 pub struct DefaultChangeToken {
     once: bool,
     changed: AtomicBool,
-    callbacks: RwLock<
-        Vec<(
-            Weak<dyn Fn(Option<Arc<dyn Any>>) + Send + Sync>,
-            Option<Arc<dyn Any>>,
-        )>,
-    >,
+    version: AtomicU64,
+    callbacks: Arc<Callbacks>,
+    dispatcher: Option<Arc<dyn Dispatcher>>,
 }
 
 impl DefaultChangeToken {
-    pub(crate) fn once() -> Self {
+    pub(crate) fn once(dispatcher: Option<Arc<dyn Dispatcher>>) -> Self {
         Self {
             once: true,
+            dispatcher,
             ..Default::default()
         }
     }
@@ -33,28 +264,102 @@ impl DefaultChangeToken {
         Self::default()
     }
 
+    /// Initializes a new default change token that invokes callbacks using the specified dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The [dispatcher](crate::Dispatcher) used to invoke registered callbacks
+    ///
+    /// # Remarks
+    ///
+    /// A notification dispatches each callback and returns without waiting for it to be invoked.
+    /// A callback that is unregistered before the dispatcher executes it is not invoked. Panics
+    /// are only reported to the notifier if they occur while dispatching.
+    ///
+    /// Every callback is dispatched, including a callback registered with state. The state is
+    /// provided to the callback on the thread the dispatcher invokes it on.
+    pub fn with_dispatcher(dispatcher: Arc<dyn Dispatcher>) -> Self {
+        Self {
+            dispatcher: Some(dispatcher),
+            ..Default::default()
+        }
+    }
+
     /// Notifies any registered callbacks of a change.
+    ///
+    /// # Remarks
+    ///
+    /// Each notification increments the [version](ChangeToken::version) of the token. If a
+    /// notification occurs while callbacks are being invoked for a previous notification,
+    /// the version is incremented, but callbacks are not invoked again.
+    ///
+    /// A callback that panics does not prevent the remaining callbacks from being invoked
+    /// nor does it leave the token in an invalid state. After all callbacks have been invoked,
+    /// the first panic is resumed on the calling thread. Use [`try_notify`](Self::try_notify)
+    /// to handle the panics instead.
     pub fn notify(&self) {
+        if let Err(error) = self.try_notify() {
+            resume_unwind(error.into_panics().remove(0))
+        }
+    }
+
+    /// Notifies any registered callbacks of a change.
+    ///
+    /// # Returns
+    ///
+    /// An [error](NotifyError) containing the panics of any callbacks that panicked.
+    ///
+    /// # Remarks
+    ///
+    /// Every callback is invoked even if a previous callback panics.
+    pub fn try_notify(&self) -> Result<(), NotifyError> {
+        let mut panics = Vec::new();
         let result = self
             .changed
             .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst);
 
+        if result.is_err() && !self.once {
+            // a change is already in flight; record it so consumers
+            // comparing versions can tell that a change was missed
+            self.version.fetch_add(1, Ordering::SeqCst);
+            self.callbacks.wake();
+        }
+
         if let Ok(notified) = result {
             if !notified {
-                // acquire a read-lock and capture any callbacks that are still alive.
-                // do NOT invoke the callback with the read-lock held. the callback might
-                // register a new callback on the same token which will result in a deadlock.
-                // invoking the callbacks after the read-lock is released ensures that won't happen.
-                let callbacks: Vec<_> = self
-                    .callbacks
-                    .read()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|r| r.0.upgrade().map(|c| (c, r.1.clone())))
-                    .collect();
-
-                for (callback, state) in callbacks {
-                    callback(state);
+                let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+
+                self.callbacks.wake();
+
+                // only one notification at a time can reach this point so the snapshot is
+                // never rebuilt concurrently. no lock is held while callbacks are invoked so
+                // a callback can safely register a new callback on the same token.
+                let callbacks = self.callbacks.snapshot();
+
+                for entry in callbacks.iter() {
+                    if let Some(slot) = entry.slot.upgrade() {
+                        let result = if let Some(dispatcher) = &self.dispatcher {
+                            let invocation = Invocation { slot, version };
+                            catch_unwind(AssertUnwindSafe(|| {
+                                dispatcher.dispatch(Box::new(move || invocation.invoke()))
+                            }))
+                        } else if let Some(callback) = slot.fire() {
+                            catch_unwind(AssertUnwindSafe(|| callback(version)))
+                        } else {
+                            Ok(())
+                        };
+
+                        if let Err(panic) = result {
+                            panics.push(panic);
+                        }
+                    }
+                }
+
+                drop(callbacks);
+
+                if self.once {
+                    // the token will never change again so release the callbacks
+                    self.callbacks.clear();
                 }
 
                 self.changed
@@ -62,6 +367,179 @@ impl DefaultChangeToken {
                     .ok();
             }
         }
+
+        if panics.is_empty() {
+            Ok(())
+        } else {
+            Err(NotifyError(panics))
+        }
+    }
+
+    /// Registers for a callback that will be invoked with the token version when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    pub fn register_versioned(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+    ) -> Registration {
+        self.register_with_priority(callback, state, 0)
+    }
+
+    /// Registers for a callback that will be invoked in priority order when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    /// * `priority` - The priority of the callback relative to other callbacks
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// Callbacks with a higher priority are invoked before callbacks with a lower priority.
+    /// Callbacks with the same priority are invoked in the order they were registered. Callbacks
+    /// registered by any other function have a priority of zero. When the token has a
+    /// [dispatcher](crate::Dispatcher), callbacks are dispatched in this order, but the
+    /// order in which they are invoked is determined by the dispatcher.
+    pub fn register_with_priority(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+    ) -> Registration {
+        self.insert(callback, state, priority, false)
+    }
+
+    /// Registers for a callback that will be invoked only the next time the token changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// The callback is unregistered as soon as it is invoked, even if the registration is still held.
+    /// The callback is never invoked more than once, even if concurrent notifications occur.
+    pub fn register_once(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.insert(Box::new(move |_, state| callback(state)), state, 0, true)
+    }
+
+    /// Creates a future that completes when the token changes.
+    ///
+    /// # Returns
+    ///
+    /// A [future](crate::Changed) that completes with the [version](ChangeToken::version) of the change.
+    ///
+    /// # Remarks
+    ///
+    /// The future completes after the next change that occurs once the future has been created. The
+    /// future does not require an async runtime; its waker is stored alongside the registered callbacks
+    /// and is woken by the notification.
+    pub fn wait_async(&self) -> Changed<'_> {
+        Changed::new(self, self.baseline())
+    }
+
+    /// Creates a stream of the changes to the token.
+    ///
+    /// # Returns
+    ///
+    /// A [stream](crate::Changes) that yields the [version](ChangeToken::version) of each change.
+    ///
+    /// # Remarks
+    ///
+    /// Changes that occur faster than the stream is polled are coalesced into a single item
+    /// with the latest version.
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    pub fn changes(&self) -> Changes<'_> {
+        Changes::new(self, self.baseline())
+    }
+
+    // gets the version that a change must exceed to be observed. a token that
+    // changes at most once is observed even if it changed before the consumer
+    // started waiting, which is consistent with register_or_invoke
+    pub(crate) fn baseline(&self) -> u64 {
+        if self.single() {
+            0
+        } else {
+            self.version()
+        }
+    }
+
+    // gets a value indicating whether the token changes at most once
+    #[cfg_attr(not(feature = "futures"), allow(dead_code))]
+    pub(crate) fn single(&self) -> bool {
+        self.once
+    }
+
+    pub(crate) fn poll_change(
+        &self,
+        cx: &mut Context<'_>,
+        baseline: u64,
+        key: &mut Option<Key>,
+    ) -> Poll<u64> {
+        let version = self.version();
+
+        if version > baseline {
+            return Poll::Ready(version);
+        }
+
+        *key = Some(self.callbacks.park(*key, cx.waker()));
+
+        // a notification that occurred before the waker was parked
+        // might not have woken it so check the version again
+        let version = self.version();
+
+        if version > baseline {
+            Poll::Ready(version)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    pub(crate) fn unpark(&self, key: Key) {
+        self.callbacks.unpark(key)
+    }
+
+    fn insert(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+        once: bool,
+    ) -> Registration {
+        Registration::slot(self.slot(callback, state, priority, once))
+    }
+
+    fn slot(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+        once: bool,
+    ) -> Arc<Slot<Handler>> {
+        let owner: Arc<dyn Release> = self.callbacks.clone();
+        self.callbacks
+            .insert(callback, state, priority, once, Arc::downgrade(&owner))
     }
 }
 
@@ -74,23 +552,32 @@ impl ChangeToken for DefaultChangeToken {
         self.changed.load(Ordering::SeqCst)
     }
 
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
-        let mut callbacks = self.callbacks.write().unwrap();
-
-        // writes are much infrequent and we already need to escalate
-        // to a write-lock, so do the trimming of any dead callbacks now
-        if !callbacks.is_empty() {
-            for i in (0..callbacks.len()).rev() {
-                if callbacks[i].0.upgrade().is_none() {
-                    callbacks.remove(i);
-                }
-            }
+        self.register_versioned(Box::new(move |_, state| callback(state)), state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        if !self.once {
+            // the change has already been delivered to every callback
+            return self.register(callback, state);
         }
 
-        let source: Arc<dyn Fn(Option<Arc<dyn Any>>) + Send + Sync> = Arc::from(callback);
+        // the callback can only fire once so either the notification or this
+        // function invokes the callback, whichever claims the slot first. a
+        // notification that starts after the slot is inserted always sees it
+        let slot = self.slot(Box::new(move |_, state| callback(state)), state, 0, true);
+
+        if self.changed() {
+            if let Some(callback) = slot.fire() {
+                callback(self.version());
+            }
+        }
 
-        callbacks.push((Arc::downgrade(&source), state));
-        Registration::new(source)
+        Registration::slot(slot)
     }
 }
 
@@ -101,9 +588,13 @@ unsafe impl Sync for DefaultChangeToken {}
 mod tests {
 
     use super::*;
-    use std::sync::{
-        atomic::{AtomicU8, Ordering},
-        Arc,
+    use crate::Work;
+    use std::{
+        sync::{
+            atomic::{AtomicU8, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread,
     };
 
     #[test]
@@ -164,4 +655,571 @@ mod tests {
         // assert
         assert_eq!(counter.load(Ordering::SeqCst), 2);
     }
+
+    #[test]
+    fn version_should_be_zero_when_unchanged() {
+        // arrange
+        let token = DefaultChangeToken::default();
+
+        // act
+        let version = token.version();
+
+        // assert
+        assert_eq!(version, 0);
+    }
+
+    #[test]
+    fn notify_should_increment_version() {
+        // arrange
+        let token = DefaultChangeToken::default();
+
+        token.notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(token.version(), 2);
+    }
+
+    #[test]
+    fn notify_should_increment_version_when_notified_during_callback() {
+        // arrange
+        let token = Arc::new(DefaultChangeToken::default());
+        let _registration = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<DefaultChangeToken>()
+                    .unwrap()
+                    .notify();
+            }),
+            Some(token.clone()),
+        );
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(token.version(), 2);
+    }
+
+    #[test]
+    fn versioned_callback_should_receive_version() {
+        // arrange
+        let versions = Arc::new(RwLock::new(Vec::<u64>::new()));
+        let token = DefaultChangeToken::default();
+        let _registration = token.register_versioned(
+            Box::new(|version, state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<RwLock<Vec<u64>>>()
+                    .unwrap()
+                    .write()
+                    .unwrap()
+                    .push(version);
+            }),
+            Some(versions.clone()),
+        );
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        assert_eq!(*versions.read().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn registration_should_be_active_when_registered() {
+        // arrange
+        let token = DefaultChangeToken::default();
+
+        // act
+        let registration = token.register(Box::new(|_| {}), None);
+
+        // assert
+        assert!(registration.is_active());
+    }
+
+    #[test]
+    fn registration_should_not_be_active_when_token_is_dropped() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registration = token.register(Box::new(|_| {}), None);
+
+        // act
+        drop(token);
+
+        // assert
+        assert!(!registration.is_active());
+    }
+
+    #[test]
+    fn registration_should_indicate_callback_fired() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registration = token.register(Box::new(|_| {}), None);
+        let fired = registration.fired();
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(!fired);
+        assert!(registration.fired());
+    }
+
+    #[test]
+    fn state_should_be_released_when_registration_is_dropped_after_notify() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let state: Arc<dyn Any> = Arc::new(1u8);
+        let registration = token.register(Box::new(|_| {}), Some(state.clone()));
+
+        token.notify();
+
+        // act
+        drop(registration);
+
+        // assert
+        assert_eq!(Arc::strong_count(&state), 1);
+    }
+
+    #[test]
+    fn notify_should_invoke_callback_when_other_registrations_are_dropped_concurrently() {
+        // arrange
+        let token = Arc::new(DefaultChangeToken::default());
+        let counter = Arc::new(AtomicUsize::default());
+        let invocations = counter.clone();
+        let _registration = token.register(
+            Box::new(move |_| {
+                invocations.fetch_add(1, Ordering::SeqCst);
+            }),
+            None,
+        );
+        let stop = Arc::new(AtomicBool::default());
+        let churn = {
+            let token = token.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    drop(token.register(Box::new(|_| {}), None));
+                }
+            })
+        };
+
+        // act
+        for _ in 0..10_000 {
+            token.notify();
+        }
+
+        stop.store(true, Ordering::SeqCst);
+        churn.join().unwrap();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 10_000);
+    }
+
+    #[test]
+    fn unregister_should_return_true_when_callback_is_registered() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registration = token.register(Box::new(|_| {}), None);
+
+        // act
+        let registered = registration.unregister();
+
+        // assert
+        assert!(registered);
+    }
+
+    #[test]
+    fn unregister_should_return_false_when_token_is_dropped() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registration = token.register(Box::new(|_| {}), None);
+
+        drop(token);
+
+        // act
+        let registered = registration.unregister();
+
+        // assert
+        assert!(!registered);
+    }
+
+    #[test]
+    fn callback_should_not_be_invoked_after_unregister() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let registration = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        registration.unregister();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn dropped_registration_should_be_removed_immediately() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registrations: Vec<_> = (0..10)
+            .map(|_| token.register(Box::new(|_| {}), None))
+            .collect();
+
+        // act
+        drop(registrations);
+
+        // assert
+        assert_eq!(token.callbacks.entries.read().unwrap().values().count(), 0);
+    }
+
+    #[test]
+    fn unregister_should_return_false_when_once_callback_has_fired() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let registration = token.register_once(Box::new(|_| {}), None);
+
+        token.notify();
+
+        // act
+        let registered = registration.unregister();
+
+        // assert
+        assert!(!registered);
+    }
+
+    #[test]
+    fn unregister_should_not_remove_other_registrations() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let first = token.register(Box::new(|_| {}), None);
+        let _second = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        first.unregister();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn notify_should_invoke_remaining_callbacks_when_callback_panics() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let _first = token.register(Box::new(|_| panic!("expected")), None);
+        let _second = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // act
+        let result = catch_unwind(AssertUnwindSafe(|| token.notify()));
+
+        // assert
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn notify_should_succeed_after_callback_panics() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let panicked = Arc::new(AtomicBool::default());
+        let _first = token.register(
+            Box::new(|state| {
+                let panicked = state.unwrap();
+                let panicked = panicked.downcast_ref::<AtomicBool>().unwrap();
+
+                if !panicked.swap(true, Ordering::SeqCst) {
+                    panic!("expected");
+                }
+            }),
+            Some(panicked),
+        );
+        let _second = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        let _ = catch_unwind(AssertUnwindSafe(|| token.notify()));
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(!token.changed());
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn try_notify_should_return_panics() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let _first = token.register(Box::new(|_| panic!("first")), None);
+        let _second = token.register(Box::new(|_| panic!("second")), None);
+
+        // act
+        let error = token.try_notify().unwrap_err();
+
+        // assert
+        assert_eq!(format!("{:?}", error), r#"["first", "second"]"#);
+    }
+
+    #[test]
+    fn register_once_should_invoke_callback_only_once() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let registration = token.register_once(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        token.notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(registration.fired());
+        assert!(!registration.is_active());
+        assert_eq!(token.callbacks.entries.read().unwrap().values().count(), 0);
+    }
+
+    #[test]
+    fn register_once_should_not_invoke_callback_when_notified_during_callback() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = Arc::new(DefaultChangeToken::default());
+        let other = token.clone();
+        let _registration = token.register_once(
+            Box::new(move |state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+                other.notify();
+            }),
+            Some(counter.clone()),
+        );
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    fn record(order: &Arc<Mutex<Vec<u8>>>, id: u8) -> VersionedCallback {
+        let order = order.clone();
+        Box::new(move |_, _| order.lock().unwrap().push(id))
+    }
+
+    #[test]
+    fn notify_should_invoke_callbacks_in_priority_order() {
+        // arrange
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let token = DefaultChangeToken::default();
+        let _low = token.register_with_priority(record(&order, 1), None, -1);
+        let _default = token.register_versioned(record(&order, 2), None);
+        let _high = token.register_with_priority(record(&order, 3), None, 1);
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(*order.lock().unwrap(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn notify_should_invoke_callbacks_with_same_priority_in_registration_order() {
+        // arrange
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let token = DefaultChangeToken::default();
+        let first = token.register_versioned(record(&order, 1), None);
+        let _second = token.register_versioned(record(&order, 2), None);
+
+        // the third registration reuses the entry released by the first
+        first.unregister();
+
+        let _third = token.register_versioned(record(&order, 3), None);
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(*order.lock().unwrap(), vec![2, 3]);
+    }
+
+    fn queue() -> (Arc<Mutex<Vec<Work>>>, Arc<dyn Dispatcher>) {
+        let queue = Arc::new(Mutex::new(Vec::<Work>::new()));
+        let pending = queue.clone();
+        let dispatcher = Arc::new(move |work| pending.lock().unwrap().push(work));
+        (queue, dispatcher)
+    }
+
+    #[test]
+    fn notify_should_invoke_callback_using_dispatcher() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let (queue, dispatcher) = queue();
+        let token = DefaultChangeToken::with_dispatcher(dispatcher);
+        let invocations = counter.clone();
+        let _registration = token.register(
+            Box::new(move |_| {
+                invocations.fetch_add(1, Ordering::SeqCst);
+            }),
+            None,
+        );
+
+        token.notify();
+
+        let invoked = counter.load(Ordering::SeqCst);
+
+        // act
+        for work in queue.lock().unwrap().drain(..) {
+            work();
+        }
+
+        // assert
+        assert_eq!(invoked, 0);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn notify_should_dispatch_callback_registered_with_state() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let (queue, dispatcher) = queue();
+        let token = DefaultChangeToken::with_dispatcher(dispatcher);
+        let _registration = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        token.notify();
+
+        let invoked = counter.load(Ordering::SeqCst);
+
+        // act
+        for work in queue.lock().unwrap().drain(..) {
+            work();
+        }
+
+        // assert
+        assert_eq!(invoked, 0);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn dispatched_callback_should_not_be_invoked_after_unregister() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let (queue, dispatcher) = queue();
+        let token = DefaultChangeToken::with_dispatcher(dispatcher);
+        let invocations = counter.clone();
+        let registration = token.register(
+            Box::new(move |_| {
+                invocations.fetch_add(1, Ordering::SeqCst);
+            }),
+            None,
+        );
+
+        token.notify();
+        registration.unregister();
+
+        // act
+        for work in queue.lock().unwrap().drain(..) {
+            work();
+        }
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn token_should_be_usable_when_lock_is_poisoned() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = token.callbacks.entries.write().unwrap();
+            panic!("expected");
+        }));
+        let registration = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(token.callbacks.entries.is_poisoned());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(registration.unregister());
+    }
 }
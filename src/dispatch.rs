@@ -0,0 +1,308 @@
+use crate::{sync::MutexExt, Callback, CallbackRef};
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Represents a unit of work scheduled by a [`Dispatcher`].
+pub type Work = Box<dyn FnOnce() + Send>;
+
+/// Defines the behavior of an object that determines where and when change token callbacks are invoked.
+///
+/// # Remarks
+///
+/// Any `Fn(Work)` is a dispatcher, which allows callbacks to be forwarded to a
+/// user-defined executor such as the event queue of a UI thread.
+pub trait Dispatcher: Send + Sync {
+    /// Dispatches the specified work.
+    ///
+    /// # Arguments
+    ///
+    /// * `work` - The [work](Work) to execute
+    fn dispatch(&self, work: Work);
+}
+
+impl<F: Fn(Work) + Send + Sync> Dispatcher for F {
+    fn dispatch(&self, work: Work) {
+        self(work)
+    }
+}
+
+/// Represents a [`Dispatcher`] that executes work immediately on the calling thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InlineDispatcher;
+
+impl Dispatcher for InlineDispatcher {
+    fn dispatch(&self, work: Work) {
+        work()
+    }
+}
+
+// represents a set of threads that execute work from a shared queue
+struct Workers {
+    sender: Option<Sender<Work>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Workers {
+    fn new(size: usize) -> Self {
+        let (sender, receiver) = channel::<Work>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = (0..size.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || Self::run(&receiver))
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    fn run(receiver: &Mutex<Receiver<Work>>) {
+        loop {
+            // the lock is released before the work is executed
+            let work = receiver.lock_or_recover().recv();
+
+            match work {
+                // a panic is isolated to the work that caused it
+                Ok(work) => {
+                    catch_unwind(AssertUnwindSafe(work)).ok();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn dispatch(&self, work: Work) {
+        if let Some(sender) = &self.sender {
+            sender.send(work).ok();
+        }
+    }
+}
+
+impl Drop for Workers {
+    fn drop(&mut self) {
+        // closing the queue lets the threads finish any pending work and exit. the
+        // dispatcher might be dropped by its own work, which must not wait on itself
+        drop(self.sender.take());
+
+        let current = thread::current().id();
+
+        for handle in self.threads.drain(..) {
+            if handle.thread().id() != current {
+                handle.join().ok();
+            }
+        }
+    }
+}
+
+/// Represents a [`Dispatcher`] that executes work sequentially on a dedicated thread.
+///
+/// # Remarks
+///
+/// Work is executed in the order it is dispatched. When the dispatcher is dropped,
+/// any pending work is executed before the thread exits.
+pub struct ThreadDispatcher(Workers);
+
+impl ThreadDispatcher {
+    /// Initializes a new dedicated thread dispatcher.
+    pub fn new() -> Self {
+        Self(Workers::new(1))
+    }
+}
+
+impl Default for ThreadDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dispatcher for ThreadDispatcher {
+    fn dispatch(&self, work: Work) {
+        self.0.dispatch(work)
+    }
+}
+
+/// Represents a [`Dispatcher`] that executes work concurrently on a fixed set of threads.
+///
+/// # Remarks
+///
+/// Work is started in the order it is dispatched, but may complete in any order.
+/// When the dispatcher is dropped, any pending work is executed before the threads exit.
+pub struct ThreadPoolDispatcher(Workers);
+
+impl ThreadPoolDispatcher {
+    /// Initializes a new thread pool dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of threads in the pool, which is at least one
+    pub fn new(size: usize) -> Self {
+        Self(Workers::new(size))
+    }
+}
+
+impl Dispatcher for ThreadPoolDispatcher {
+    fn dispatch(&self, work: Work) {
+        self.0.dispatch(work)
+    }
+}
+
+/// Creates a callback that is invoked by the specified dispatcher.
+///
+/// # Arguments
+///
+/// * `dispatcher` - The [dispatcher](Dispatcher) used to invoke the callback
+/// * `callback` - The callback to invoke
+/// * `state` - The optional state provided to the callback, if any
+///
+/// # Returns
+///
+/// A new [callback](crate::Callback) that can be registered with any [`ChangeToken`](crate::ChangeToken).
+///
+/// # Remarks
+///
+/// This specifies a dispatcher for a single registration. Unlike a dispatcher provided to a change
+/// token, the callback may still be invoked if it is unregistered after it has been dispatched.
+///
+/// The state is sent to the dispatcher, so it is provided here rather than when the callback is
+/// registered. Any state provided when the returned callback is registered is ignored.
+pub fn dispatched(
+    dispatcher: Arc<dyn Dispatcher>,
+    callback: Callback,
+    state: Option<Arc<dyn Any + Send + Sync>>,
+) -> Callback {
+    let callback: CallbackRef = Arc::from(callback);
+
+    Box::new(move |_| {
+        let callback = callback.clone();
+        let state = state.clone();
+        dispatcher.dispatch(Box::new(move || callback(state.map(|s| s as Arc<dyn Any>))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{ChangeToken, DefaultChangeToken};
+    use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+    #[test]
+    fn inline_dispatcher_should_execute_work_on_calling_thread() {
+        // arrange
+        let dispatcher = InlineDispatcher;
+        let current = thread::current().id();
+        let same = Arc::new(AtomicBool::default());
+        let result = same.clone();
+
+        // act
+        dispatcher.dispatch(Box::new(move || {
+            result.store(thread::current().id() == current, Ordering::SeqCst)
+        }));
+
+        // assert
+        assert!(same.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn thread_dispatcher_should_execute_work_on_another_thread() {
+        // arrange
+        let dispatcher = ThreadDispatcher::new();
+        let current = thread::current().id();
+        let other = Arc::new(AtomicBool::default());
+        let result = other.clone();
+
+        dispatcher.dispatch(Box::new(move || {
+            result.store(thread::current().id() != current, Ordering::SeqCst)
+        }));
+
+        // act
+        drop(dispatcher);
+
+        // assert
+        assert!(other.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn thread_dispatcher_should_execute_work_after_panic() {
+        // arrange
+        let dispatcher = ThreadDispatcher::new();
+        let executed = Arc::new(AtomicBool::default());
+        let result = executed.clone();
+
+        dispatcher.dispatch(Box::new(|| panic!("expected")));
+        dispatcher.dispatch(Box::new(move || result.store(true, Ordering::SeqCst)));
+
+        // act
+        drop(dispatcher);
+
+        // assert
+        assert!(executed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn thread_pool_dispatcher_should_execute_all_work() {
+        // arrange
+        let dispatcher = ThreadPoolDispatcher::new(4);
+        let counter = Arc::new(AtomicU8::default());
+
+        for _ in 0..10 {
+            let counter = counter.clone();
+            dispatcher.dispatch(Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+
+        // act
+        drop(dispatcher);
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn dispatched_callback_should_be_invoked_by_dispatcher() {
+        // arrange
+        let queue = Arc::new(Mutex::new(Vec::<Work>::new()));
+        let pending = queue.clone();
+        let dispatcher = Arc::new(move |work| pending.lock().unwrap().push(work));
+        let counter = Arc::new(AtomicU8::default());
+        let token = DefaultChangeToken::default();
+        let _registration = token.register(
+            dispatched(
+                dispatcher,
+                Box::new(|state| {
+                    state
+                        .unwrap()
+                        .downcast_ref::<AtomicU8>()
+                        .unwrap()
+                        .fetch_add(1, Ordering::SeqCst);
+                }),
+                Some(counter.clone()),
+            ),
+            None,
+        );
+
+        token.notify();
+
+        let invoked = counter.load(Ordering::SeqCst);
+
+        // act
+        for work in queue.lock().unwrap().drain(..) {
+            work();
+        }
+
+        // assert
+        assert_eq!(invoked, 0);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Callback, ChangeToken, PollingChangeToken, Registration};
+use std::{
+    any::Any,
+    env,
+    ffi::{OsStr, OsString},
+    sync::Arc,
+};
+
+type Snapshot = Vec<(OsString, Option<OsString>)>;
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) for environment variables.
+///
+/// # Remarks
+///
+/// The environment variables are captured when the token is created. The token changes when any
+/// of the variables is added, removed, or has a different value. The environment is checked using
+/// a [`PollingChangeToken`](crate::PollingChangeToken), so registered notifications always occur
+/// on another thread and the token changes at most once.
+pub struct EnvironmentChangeToken(PollingChangeToken);
+
+impl EnvironmentChangeToken {
+    /// Initializes a new environment change token for the specified variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names of the environment variables to watch for changes
+    pub fn new<I, K>(names: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<OsStr>,
+    {
+        let names: Vec<_> = names
+            .into_iter()
+            .map(|name| name.as_ref().to_os_string())
+            .collect();
+
+        Self::watch(move || {
+            names
+                .iter()
+                .map(|name| (name.clone(), env::var_os(name)))
+                .collect()
+        })
+    }
+
+    /// Initializes a new environment change token for the variables that start with the specified prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the environment variables to watch for changes
+    ///
+    /// # Remarks
+    ///
+    /// Environment variables whose names are not valid Unicode are ignored.
+    pub fn with_prefix<S: Into<String>>(prefix: S) -> Self {
+        let prefix = prefix.into();
+
+        Self::watch(move || {
+            let mut variables: Snapshot = env::vars_os()
+                .filter(|(name, _)| name.to_str().is_some_and(|n| n.starts_with(&prefix)))
+                .map(|(name, value)| (name, Some(value)))
+                .collect();
+            variables.sort();
+            variables
+        })
+    }
+
+    fn watch<F: Fn() -> Snapshot + Send + Sync + 'static>(snapshot: F) -> Self {
+        let original = snapshot();
+        Self(PollingChangeToken::new(move || snapshot() != original))
+    }
+}
+
+impl ChangeToken for EnvironmentChangeToken {
+    fn changed(&self) -> bool {
+        self.0.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.0.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.0.register_or_invoke(callback, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Wait;
+    use std::time::Duration;
+
+    #[test]
+    fn environment_change_token_should_be_unchanged() {
+        // arrange
+        env::set_var("TOKENS_TEST_ENV_1", "original");
+
+        let token = EnvironmentChangeToken::new(["TOKENS_TEST_ENV_1"]);
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(!changed);
+    }
+
+    #[test]
+    fn environment_change_token_should_change_when_variable_changes() {
+        // arrange
+        env::set_var("TOKENS_TEST_ENV_2", "original");
+
+        let token = EnvironmentChangeToken::new(["TOKENS_TEST_ENV_2"]);
+
+        env::set_var("TOKENS_TEST_ENV_2", "updated");
+
+        // act
+        let changed = token.wait_timeout(Duration::from_secs(5));
+
+        // assert
+        assert!(changed);
+    }
+
+    #[test]
+    fn environment_change_token_should_change_when_variable_is_removed() {
+        // arrange
+        env::set_var("TOKENS_TEST_ENV_3", "original");
+
+        let token = EnvironmentChangeToken::new(["TOKENS_TEST_ENV_3"]);
+
+        env::remove_var("TOKENS_TEST_ENV_3");
+
+        // act
+        let changed = token.wait_timeout(Duration::from_secs(5));
+
+        // assert
+        assert!(changed);
+    }
+
+    #[test]
+    fn environment_change_token_should_change_when_variable_with_prefix_is_added() {
+        // arrange
+        let token = EnvironmentChangeToken::with_prefix("TOKENS_TEST_PREFIX_");
+
+        env::set_var("TOKENS_TEST_PREFIX_1", "added");
+
+        // act
+        let changed = token.wait_timeout(Duration::from_secs(5));
+
+        // assert
+        assert!(changed);
+    }
+
+    #[test]
+    fn environment_change_token_should_ignore_variable_without_prefix() {
+        // arrange
+        let token = EnvironmentChangeToken::with_prefix("TOKENS_TEST_OTHER_");
+
+        env::set_var("TOKENS_TEST_ENV_4", "added");
+
+        // act
+        let changed = token.wait_timeout(Duration::from_millis(250));
+
+        // assert
+        assert!(!changed);
+    }
+}
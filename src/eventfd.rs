@@ -0,0 +1,200 @@
+use crate::{Callback, ChangeToken, Registration};
+use std::{
+    any::Any,
+    io::{Error, Result},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    sync::Arc,
+};
+
+const COUNTER: usize = std::mem::size_of::<u64>();
+
+fn write(fd: &OwnedFd, value: u64) -> Result<()> {
+    let buffer = value.to_ne_bytes();
+
+    if unsafe { libc::write(fd.as_raw_fd(), buffer.as_ptr().cast(), COUNTER) } < 0 {
+        Err(Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn read(fd: &OwnedFd) -> Result<u64> {
+    let mut buffer = [0u8; COUNTER];
+
+    if unsafe { libc::read(fd.as_raw_fd(), buffer.as_mut_ptr().cast(), COUNTER) } < 0 {
+        Err(Error::last_os_error())
+    } else {
+        Ok(u64::from_ne_bytes(buffer))
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that exposes a pollable Linux `eventfd`.
+///
+/// # Remarks
+///
+/// The file descriptor becomes readable when the observed token changes and remains readable until
+/// the changes are taken with [`take_changes`](Self::take_changes). This allows event loops that do not
+/// invoke Rust callbacks, such as `epoll`, `mio`, or C libraries, to wait on change tokens directly.
+/// The file descriptor is non-blocking and is closed when the token is dropped.
+pub struct EventFdChangeToken {
+    _registration: Registration,
+    token: Box<dyn ChangeToken>,
+    fd: Arc<OwnedFd>,
+}
+
+impl EventFdChangeToken {
+    /// Initializes a new eventfd change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The [change token](crate::ChangeToken) to observe
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the eventfd cannot be created.
+    ///
+    /// # Remarks
+    ///
+    /// A token that [must be polled](crate::ChangeToken::must_poll) does not invoke
+    /// callbacks and, therefore, never makes the file descriptor readable.
+    pub fn new<T: ChangeToken + 'static>(token: T) -> Result<Self> {
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let fd = Arc::new(unsafe { OwnedFd::from_raw_fd(fd) });
+        let signal = fd.clone();
+        let registration = token.register_or_invoke(
+            // the write only fails if the counter would overflow,
+            // in which case the descriptor is already readable
+            Box::new(move |_| write(&signal, 1).unwrap_or_default()),
+            None,
+        );
+
+        Ok(Self {
+            _registration: registration,
+            token: Box::new(token),
+            fd,
+        })
+    }
+
+    /// Takes the number of changes that have occurred since the changes were last taken.
+    ///
+    /// # Remarks
+    ///
+    /// The file descriptor is no longer readable until the next change occurs.
+    pub fn take_changes(&self) -> u64 {
+        // the read only fails when no change has occurred
+        read(&self.fd).unwrap_or_default()
+    }
+}
+
+impl ChangeToken for EventFdChangeToken {
+    fn changed(&self) -> bool {
+        self.token.changed()
+    }
+
+    fn must_poll(&self) -> bool {
+        self.token.must_poll()
+    }
+
+    fn version(&self) -> u64 {
+        self.token.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.token.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.token.register_or_invoke(callback, state)
+    }
+}
+
+impl AsFd for EventFdChangeToken {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for EventFdChangeToken {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{DefaultChangeToken, SharedChangeToken, SingleChangeToken};
+
+    fn readable<T: AsRawFd>(source: &T) -> bool {
+        let mut fd = libc::pollfd {
+            fd: source.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        unsafe { libc::poll(&mut fd, 1, 0) > 0 }
+    }
+
+    #[test]
+    fn eventfd_should_not_be_readable_when_token_is_unchanged() {
+        // arrange
+        let token = EventFdChangeToken::new(DefaultChangeToken::new()).unwrap();
+
+        // act
+        let readable = readable(&token);
+
+        // assert
+        assert!(!readable);
+    }
+
+    #[test]
+    fn eventfd_should_be_readable_when_token_changes() {
+        // arrange
+        let source = SharedChangeToken::<DefaultChangeToken>::default();
+        let token = EventFdChangeToken::new(source.clone()).unwrap();
+
+        // act
+        source.notify();
+
+        // assert
+        assert!(readable(&token));
+    }
+
+    #[test]
+    fn eventfd_should_be_readable_when_token_has_already_changed() {
+        // arrange
+        let source = SingleChangeToken::new();
+
+        source.notify();
+
+        // act
+        let token = EventFdChangeToken::new(source).unwrap();
+
+        // assert
+        assert!(readable(&token));
+    }
+
+    #[test]
+    fn take_changes_should_return_changes_and_reset_eventfd() {
+        // arrange
+        let source = SharedChangeToken::<DefaultChangeToken>::default();
+        let token = EventFdChangeToken::new(source.clone()).unwrap();
+
+        source.notify();
+        source.notify();
+
+        // act
+        let changes = token.take_changes();
+
+        // assert
+        assert_eq!(changes, 2);
+        assert!(!readable(&token));
+        assert_eq!(token.take_changes(), 0);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{Callback, ChangeToken, Registration, SingleChangeToken};
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use std::any::Any;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
 use std::mem::ManuallyDrop;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
@@ -18,6 +21,53 @@ pub struct FileChangeToken {
     inner: Arc<SingleChangeToken>,
 }
 
+// the maximum number of symbolic links followed, which matches the limit imposed by linux
+const MAX_LINKS: usize = 40;
+
+// resolves a path and returns the final target along with every symbolic link encountered
+fn resolve(path: &Path) -> (PathBuf, Vec<PathBuf>) {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut pending: Vec<PathBuf> = path
+        .components()
+        .rev()
+        .map(|c| c.as_os_str().into())
+        .collect();
+    let mut resolved = PathBuf::new();
+    let mut links = Vec::new();
+
+    while let Some(component) = pending.pop() {
+        match component.components().next() {
+            Some(Component::Normal(name)) => {
+                let candidate = resolved.join(name);
+
+                match fs::read_link(&candidate) {
+                    Ok(target) if links.len() < MAX_LINKS => {
+                        // an absolute target replaces everything resolved so far
+                        if target.is_absolute() {
+                            resolved = PathBuf::new();
+                        }
+
+                        pending.extend(target.components().rev().map(|c| c.as_os_str().into()));
+                        links.push(candidate);
+                    }
+                    _ => resolved = candidate,
+                }
+            }
+            Some(Component::ParentDir) => {
+                resolved.pop();
+            }
+            Some(Component::CurDir) | None => {}
+            Some(other) => resolved.push(other),
+        }
+    }
+
+    (resolved, links)
+}
+
 impl FileChangeToken {
     /// Initializes a new file change token.
     ///
@@ -49,6 +99,99 @@ impl FileChangeToken {
             inner,
         }
     }
+
+    // initializes a new file change token that watches the directory containing the file, which
+    // observes the file being created, modified, replaced, or removed even if it doesn't exist
+    pub(crate) fn entry(path: &Path) -> notify::Result<Self> {
+        let name = path.file_name().map(OsStr::to_os_string);
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let inner = Arc::new(SingleChangeToken::default());
+        let handler = inner.clone();
+        let (sender, receiver) = channel();
+        let mut watcher = RecommendedWatcher::new(sender, Config::default())?;
+
+        let handle = thread::spawn(move || {
+            // the loop ends when the watcher is dropped
+            for event in receiver.into_iter().flatten() {
+                if event.paths.iter().any(|p| p.file_name() == name.as_deref()) {
+                    handler.notify();
+                    break;
+                }
+            }
+        });
+
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            watcher: ManuallyDrop::new(watcher),
+            handle: ManuallyDrop::new(handle),
+            inner,
+        })
+    }
+
+    /// Initializes a new file change token that follows symbolic links.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the file to watch for changes
+    ///
+    /// # Remarks
+    ///
+    /// Every symbolic link in the path is resolved, including links to parent directories.
+    /// The token changes when the file it resolves to is modified or removed or when any link in the
+    /// chain is re-pointed, such as when a Kubernetes ConfigMap atomically replaces its `..data` link.
+    pub fn follow_symlinks<T: AsRef<Path>>(path: T) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let original = resolve(&path);
+        let (target, links) = original.clone();
+        let inner = Arc::new(SingleChangeToken::default());
+        let handler = inner.clone();
+        let (sender, receiver) = channel();
+        let mut watcher = RecommendedWatcher::new(sender, Config::default()).unwrap();
+
+        let handle = thread::spawn(move || {
+            // the loop ends when the watcher is dropped
+            for event in receiver.into_iter().flatten() {
+                let target = event.paths.iter().any(|p| p == &original.0);
+                let changed = if target && (event.kind.is_modify() || event.kind.is_remove()) {
+                    true
+                } else {
+                    // a link is re-pointed by replacing it in its directory. any other event
+                    // for the target might have ended its watch so the chain is resolved again
+                    resolve(&path) != original
+                };
+
+                if changed {
+                    handler.notify();
+                    break;
+                }
+            }
+        });
+
+        watcher
+            .watch(target.as_ref(), RecursiveMode::NonRecursive)
+            .unwrap();
+
+        let mut directories: Vec<_> = links.iter().filter_map(|link| link.parent()).collect();
+
+        directories.sort();
+        directories.dedup();
+
+        for directory in directories {
+            watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .unwrap();
+        }
+
+        Self {
+            watcher: ManuallyDrop::new(watcher),
+            handle: ManuallyDrop::new(handle),
+            inner,
+        }
+    }
 }
 
 impl ChangeToken for FileChangeToken {
@@ -56,9 +199,17 @@ impl ChangeToken for FileChangeToken {
         self.inner.changed()
     }
 
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
         self.inner.register(callback, state)
     }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
 }
 
 impl Drop for FileChangeToken {
@@ -71,7 +222,12 @@ impl Drop for FileChangeToken {
             let _ = ManuallyDrop::take(&mut self.watcher);
             ManuallyDrop::take(&mut self.handle)
         };
-        handle.join().ok();
+
+        // the token might be dropped by one of its own callbacks,
+        // which must not wait on the thread that is invoking it
+        if handle.thread().id() != thread::current().id() {
+            handle.join().ok();
+        }
     }
 }
 
@@ -79,6 +235,8 @@ impl Drop for FileChangeToken {
 mod tests {
 
     use super::*;
+    #[cfg(unix)]
+    use crate::Wait;
     use std::env::temp_dir;
     use std::fs::{remove_file, File};
     use std::io::Write;
@@ -214,4 +372,88 @@ mod tests {
 
         assert_eq!(changed.load(Ordering::SeqCst), false);
     }
+
+    #[cfg(unix)]
+    fn config_map(name: &str) -> PathBuf {
+        use std::os::unix::fs::symlink;
+
+        // mirrors the layout kubernetes uses to project a configmap
+        let dir = temp_dir().join(name);
+
+        fs::create_dir_all(dir.join("data1")).unwrap();
+        fs::create_dir_all(dir.join("data2")).unwrap();
+        fs::write(dir.join("data1").join("key"), "original").unwrap();
+        fs::write(dir.join("data2").join("key"), "updated").unwrap();
+        remove_file(dir.join("..data")).ok();
+        remove_file(dir.join("key")).ok();
+        symlink("data1", dir.join("..data")).unwrap();
+        symlink("..data/key", dir.join("key")).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn resolve_should_return_target_and_every_link() {
+        // arrange
+        let dir = config_map("test.5");
+
+        // act
+        let (target, links) = resolve(&dir.join("key"));
+
+        // assert
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(target, dir.join("data1").join("key"));
+        assert_eq!(links, vec![dir.join("key"), dir.join("..data")]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn changed_should_be_true_when_symlink_is_repointed() {
+        // arrange
+        use std::os::unix::fs::symlink;
+
+        let dir = config_map("test.6");
+        let token = FileChangeToken::follow_symlinks(dir.join("key"));
+
+        // act
+        symlink("data2", dir.join("..data_tmp")).unwrap();
+        fs::rename(dir.join("..data_tmp"), dir.join("..data")).unwrap();
+
+        // assert
+        let changed = token.wait_timeout(Duration::from_secs(5));
+        fs::remove_dir_all(&dir).ok();
+        assert!(changed);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn changed_should_be_true_when_symlink_target_changes() {
+        // arrange
+        let dir = config_map("test.7");
+        let token = FileChangeToken::follow_symlinks(dir.join("key"));
+
+        // act
+        fs::write(dir.join("data1").join("key"), "updated").unwrap();
+
+        // assert
+        let changed = token.wait_timeout(Duration::from_secs(5));
+        fs::remove_dir_all(&dir).ok();
+        assert!(changed);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn changed_should_be_true_when_symlink_target_is_removed() {
+        // arrange
+        let dir = config_map("test.8");
+        let token = FileChangeToken::follow_symlinks(dir.join("key"));
+
+        // act
+        remove_file(dir.join("data1").join("key")).unwrap();
+
+        // assert
+        let changed = token.wait_timeout(Duration::from_secs(5));
+        fs::remove_dir_all(&dir).ok();
+        assert!(changed);
+    }
 }
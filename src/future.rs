@@ -0,0 +1,304 @@
+use crate::{DefaultChangeToken, Key};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Represents a future that completes when a change token changes.
+///
+/// # Remarks
+///
+/// The future is runtime agnostic. It only depends on the [`Waker`](std::task::Waker) provided when
+/// it is polled, which is stored by the change token and woken when the token is notified.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Changed<'a> {
+    token: &'a DefaultChangeToken,
+    baseline: u64,
+    key: Option<Key>,
+}
+
+impl<'a> Changed<'a> {
+    pub(crate) fn new(token: &'a DefaultChangeToken, baseline: u64) -> Self {
+        Self {
+            token,
+            baseline,
+            key: None,
+        }
+    }
+}
+
+impl Future for Changed<'_> {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.token.poll_change(cx, this.baseline, &mut this.key)
+    }
+}
+
+impl Drop for Changed<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.token.unpark(key);
+        }
+    }
+}
+
+/// Represents a stream of the changes to a change token.
+///
+/// # Remarks
+///
+/// The stream is runtime agnostic. It only depends on the [`Waker`](std::task::Waker) provided when
+/// it is polled, which is stored by the change token and woken when the token is notified. The
+/// stream of a token that changes at most once ends after the change.
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+#[must_use = "streams do nothing unless polled"]
+pub struct Changes<'a> {
+    token: &'a DefaultChangeToken,
+    baseline: u64,
+    key: Option<Key>,
+    once: bool,
+    done: bool,
+}
+
+#[cfg(feature = "futures")]
+impl<'a> Changes<'a> {
+    pub(crate) fn new(token: &'a DefaultChangeToken, baseline: u64) -> Self {
+        Self {
+            token,
+            baseline,
+            key: None,
+            once: token.single(),
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for Changes<'_> {
+    type Item = u64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        match this.token.poll_change(cx, this.baseline, &mut this.key) {
+            Poll::Ready(version) => {
+                this.baseline = version;
+                this.done = this.once;
+                Poll::Ready(Some(version))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::FusedStream for Changes<'_> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Drop for Changes<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.token.unpark(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{test::block_on, LatchingChangeToken, SingleChangeToken};
+    use std::{
+        sync::{
+            atomic::{AtomicU8, Ordering},
+            Arc,
+        },
+        task::{Wake, Waker},
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct Counter(AtomicU8);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn poll<F: Future + Unpin>(future: &mut F, waker: &Waker) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut Context::from_waker(waker))
+    }
+
+    #[test]
+    fn changed_should_complete_when_token_changes() {
+        // arrange
+        let token = Arc::new(DefaultChangeToken::default());
+        let notifier = token.clone();
+        let future = token.wait_async();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            notifier.notify();
+        });
+
+        // act
+        let version = block_on(future);
+
+        // assert
+        assert_eq!(version, 1);
+    }
+
+    #[test]
+    fn changed_should_wake_waker_when_token_changes() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let counter = Arc::new(Counter::default());
+        let waker = Waker::from(counter.clone());
+        let mut future = token.wait_async();
+        let pending = poll(&mut future, &waker).is_pending();
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(pending);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert_eq!(poll(&mut future, &waker), Poll::Ready(1));
+    }
+
+    #[test]
+    fn changed_should_not_complete_for_previous_change() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let waker = Waker::from(Arc::new(Counter::default()));
+
+        token.notify();
+
+        let mut future = token.wait_async();
+
+        // act
+        let result = poll(&mut future, &waker);
+
+        // assert
+        assert!(result.is_pending());
+    }
+
+    #[test]
+    fn changed_should_complete_when_single_token_has_changed() {
+        // arrange
+        let token = SingleChangeToken::default();
+
+        token.notify();
+
+        // act
+        let version = block_on(token.wait_async());
+
+        // assert
+        assert_eq!(version, 1);
+    }
+
+    #[test]
+    fn changed_should_complete_when_latching_token_is_changed() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+        token.notify();
+
+        // act
+        let version = block_on(token.wait_async());
+
+        // assert
+        assert_eq!(version, 2);
+    }
+
+    #[test]
+    fn dropped_changed_should_not_be_woken() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let counter = Arc::new(Counter::default());
+        let waker = Waker::from(counter.clone());
+        let mut future = token.wait_async();
+
+        let _ = poll(&mut future, &waker);
+        drop(future);
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "futures")]
+    fn next(stream: &mut Changes<'_>) -> Option<u64> {
+        use futures_core::Stream;
+        block_on(std::future::poll_fn(|cx| {
+            Pin::new(&mut *stream).poll_next(cx)
+        }))
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn changes_should_yield_each_change() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let mut changes = token.changes();
+        let mut versions = Vec::new();
+
+        // act
+        for _ in 0..3 {
+            token.notify();
+            versions.push(next(&mut changes));
+        }
+
+        // assert
+        assert_eq!(versions, vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn changes_should_coalesce_changes_between_polls() {
+        // arrange
+        let token = DefaultChangeToken::default();
+        let mut changes = token.changes();
+
+        token.notify();
+        token.notify();
+
+        // act
+        let version = next(&mut changes);
+
+        // assert
+        assert_eq!(version, Some(2));
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn changes_should_end_after_single_token_changes() {
+        // arrange
+        let token = SingleChangeToken::default();
+        let mut changes = token.changes();
+
+        token.notify();
+
+        // act
+        let items = (next(&mut changes), next(&mut changes));
+
+        // assert
+        assert_eq!(items, (Some(1), None));
+    }
+}
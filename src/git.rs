@@ -0,0 +1,461 @@
+use crate::{sync::MutexExt, Callback, ChangeToken, Registration, SingleChangeToken};
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    any::Any,
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+// the maximum number of symbolic references followed to resolve a commit
+const MAX_DEPTH: usize = 5;
+
+/// Represents a callback function invoked with the change to a Git reference.
+pub type RefChangeCallback = Box<dyn Fn(&RefChange, Option<Arc<dyn Any>>) + Send + Sync>;
+
+/// Represents a change to the commit checked out in a Git repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefChange {
+    old: Option<String>,
+    new: Option<String>,
+}
+
+impl RefChange {
+    /// Gets the identifier of the commit that was checked out before the change, if any.
+    pub fn old_commit(&self) -> Option<&str> {
+        self.old.as_deref()
+    }
+
+    /// Gets the identifier of the commit that is checked out after the change, if any.
+    pub fn new_commit(&self) -> Option<&str> {
+        self.new.as_deref()
+    }
+}
+
+// represents the checked out reference, if any, and the commit it resolves to
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Head {
+    reference: Option<String>,
+    commit: Option<String>,
+}
+
+struct Repository {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+impl Repository {
+    fn discover(path: &Path) -> Result<Self> {
+        let dot_git = path.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else if dot_git.is_file() {
+            // a linked worktree or submodule refers to its git directory
+            let content = fs::read_to_string(&dot_git)?;
+            match content.trim().strip_prefix("gitdir:") {
+                Some(dir) => path.join(dir.trim()),
+                _ => return Err(Self::not_found(path)),
+            }
+        } else if path.join("HEAD").is_file() {
+            path.to_path_buf()
+        } else {
+            return Err(Self::not_found(path));
+        };
+
+        // the references of a linked worktree are shared with the main repository
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(dir) => git_dir.join(dir.trim()),
+            _ => git_dir.clone(),
+        };
+
+        Ok(Self {
+            git_dir,
+            common_dir,
+        })
+    }
+
+    fn not_found(path: &Path) -> Error {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("{} is not a Git repository", path.display()),
+        )
+    }
+
+    fn head(&self) -> Option<Head> {
+        let content = fs::read_to_string(self.git_dir.join("HEAD")).ok()?;
+        let content = content.trim();
+
+        if content.is_empty() {
+            None
+        } else if let Some(reference) = content.strip_prefix("ref:") {
+            let reference = reference.trim();
+
+            Some(Head {
+                commit: self.resolve(reference, MAX_DEPTH),
+                reference: Some(reference.to_owned()),
+            })
+        } else {
+            // a detached HEAD refers to a commit directly
+            Some(Head {
+                reference: None,
+                commit: Some(content.to_owned()),
+            })
+        }
+    }
+
+    fn resolve(&self, reference: &str, depth: usize) -> Option<String> {
+        if depth == 0 {
+            return None;
+        }
+
+        if let Ok(content) = fs::read_to_string(self.common_dir.join(reference)) {
+            let content = content.trim();
+
+            if let Some(target) = content.strip_prefix("ref:") {
+                return self.resolve(target.trim(), depth - 1);
+            } else if !content.is_empty() {
+                return Some(content.to_owned());
+            }
+        }
+
+        // a reference without a loose file might have been packed
+        let packed = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+
+        packed
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+            .filter_map(|line| line.split_once(' '))
+            .find(|(_, name)| name.trim() == reference)
+            .map(|(commit, _)| commit.to_owned())
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) for the commit checked out in a Git repository.
+///
+/// # Remarks
+///
+/// The token watches `HEAD`, the reference it refers to, and `packed-refs`. The token changes when
+/// a different branch or commit is checked out, including a detached `HEAD`, or the checked out
+/// branch refers to a different commit. Registered notifications always occur on another thread.
+/// Like [`FileChangeToken`](crate::FileChangeToken), the token changes at most once.
+pub struct GitRefChangeToken {
+    _watcher: RecommendedWatcher,
+    inner: Arc<SingleChangeToken>,
+    change: Arc<Mutex<Option<RefChange>>>,
+}
+
+impl GitRefChangeToken {
+    /// Initializes a new Git reference change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the working tree or Git directory of the repository
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the path is not a Git repository or cannot be watched.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let repository = Repository::discover(path.as_ref())?;
+        let original = repository.head().unwrap_or_default();
+        let inner = Arc::new(SingleChangeToken::default());
+        let change = Arc::new(Mutex::new(None));
+        let git_dir = repository.git_dir.clone();
+        let common_dir = repository.common_dir.clone();
+        let handler = inner.clone();
+        let changed = change.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |event: notify::Result<Event>| {
+                if event.is_err() || handler.changed() {
+                    return;
+                }
+
+                // git replaces files by renaming so an unreadable HEAD is never a change
+                if let Some(head) = repository.head() {
+                    if head != original {
+                        *changed.lock_or_recover() = Some(RefChange {
+                            old: original.commit.clone(),
+                            new: head.commit,
+                        });
+                        handler.notify();
+                    }
+                }
+            },
+            Config::default(),
+        )
+        .map_err(Self::error)?;
+
+        watcher
+            .watch(&git_dir, RecursiveMode::NonRecursive)
+            .map_err(Self::error)?;
+
+        if common_dir != git_dir {
+            watcher
+                .watch(&common_dir, RecursiveMode::NonRecursive)
+                .map_err(Self::error)?;
+        }
+
+        let refs = common_dir.join("refs");
+
+        if refs.is_dir() {
+            watcher
+                .watch(&refs, RecursiveMode::Recursive)
+                .map_err(Self::error)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            inner,
+            change,
+        })
+    }
+
+    /// Gets the change to the checked out commit, if the token has changed.
+    pub fn change(&self) -> Option<RefChange> {
+        self.change.lock_or_recover().clone()
+    }
+
+    /// Registers for a callback that will be invoked with the change to the checked out commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the [change](RefChange)
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// If the token has already changed, the callback is invoked immediately.
+    pub fn register_change(
+        &self,
+        callback: RefChangeCallback,
+        state: Option<Arc<dyn Any>>,
+    ) -> Registration {
+        let change = self.change.clone();
+        self.inner.register_or_invoke(
+            Box::new(move |state| {
+                // never invoke callbacks while the lock is held
+                let change = change.lock_or_recover().clone();
+
+                if let Some(change) = &change {
+                    callback(change, state)
+                }
+            }),
+            state,
+        )
+    }
+
+    fn error(error: notify::Error) -> Error {
+        Error::other(error)
+    }
+}
+
+impl ChangeToken for GitRefChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Wait;
+    use std::{env::temp_dir, process, sync::mpsc::channel, time::Duration};
+
+    const COMMIT1: &str = "1111111111111111111111111111111111111111";
+    const COMMIT2: &str = "2222222222222222222222222222222222222222";
+
+    struct TempRepository(PathBuf);
+
+    impl TempRepository {
+        fn new(name: &str) -> Self {
+            let path = temp_dir().join(format!("tokens.{}.{}", process::id(), name));
+            let git_dir = path.join(".git");
+
+            fs::create_dir_all(git_dir.join("refs").join("heads")).unwrap();
+            fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+            Self(path)
+        }
+
+        // git updates a file by writing a lock file and renaming it
+        fn write(&self, name: &str, content: &str) {
+            let path = self.0.join(".git").join(name);
+            let lock = path.with_extension("lock");
+
+            fs::write(&lock, content).unwrap();
+            fs::rename(&lock, &path).unwrap();
+        }
+    }
+
+    impl Drop for TempRepository {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    #[test]
+    fn head_should_resolve_loose_reference() {
+        // arrange
+        let repo = TempRepository::new("git.1");
+
+        repo.write("refs/heads/main", COMMIT1);
+
+        let repository = Repository::discover(&repo.0).unwrap();
+
+        // act
+        let head = repository.head().unwrap();
+
+        // assert
+        assert_eq!(head.reference.as_deref(), Some("refs/heads/main"));
+        assert_eq!(head.commit.as_deref(), Some(COMMIT1));
+    }
+
+    #[test]
+    fn head_should_resolve_packed_reference() {
+        // arrange
+        let repo = TempRepository::new("git.2");
+
+        repo.write(
+            "packed-refs",
+            &format!(
+                "# pack-refs with: peeled fully-peeled sorted\n{} refs/heads/main\n^{}\n",
+                COMMIT2, COMMIT1
+            ),
+        );
+
+        let repository = Repository::discover(&repo.0).unwrap();
+
+        // act
+        let head = repository.head().unwrap();
+
+        // assert
+        assert_eq!(head.commit.as_deref(), Some(COMMIT2));
+    }
+
+    #[test]
+    fn head_should_resolve_detached_head() {
+        // arrange
+        let repo = TempRepository::new("git.3");
+
+        repo.write("HEAD", COMMIT1);
+
+        let repository = Repository::discover(&repo.0).unwrap();
+
+        // act
+        let head = repository.head().unwrap();
+
+        // assert
+        assert_eq!(head.reference, None);
+        assert_eq!(head.commit.as_deref(), Some(COMMIT1));
+    }
+
+    #[test]
+    fn new_should_return_error_when_path_is_not_repository() {
+        // arrange
+        let path = temp_dir().join(format!("tokens.{}.git.4", process::id()));
+
+        // act
+        let result = GitRefChangeToken::new(path);
+
+        // assert
+        assert_eq!(result.err().map(|e| e.kind()), Some(ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn git_ref_change_token_should_change_when_branch_is_updated() {
+        // arrange
+        let repo = TempRepository::new("git.5");
+
+        repo.write("refs/heads/main", COMMIT1);
+
+        let token = GitRefChangeToken::new(&repo.0).unwrap();
+
+        // act
+        repo.write("refs/heads/main", COMMIT2);
+
+        // assert
+        assert!(token.wait_timeout(Duration::from_secs(5)));
+        let change = token.change().unwrap();
+        assert_eq!(change.old_commit(), Some(COMMIT1));
+        assert_eq!(change.new_commit(), Some(COMMIT2));
+    }
+
+    #[test]
+    fn git_ref_change_token_should_change_when_head_is_detached() {
+        // arrange
+        let repo = TempRepository::new("git.6");
+
+        repo.write("refs/heads/main", COMMIT1);
+
+        let token = GitRefChangeToken::new(&repo.0).unwrap();
+
+        // act
+        repo.write("HEAD", COMMIT2);
+
+        // assert
+        assert!(token.wait_timeout(Duration::from_secs(5)));
+        let change = token.change().unwrap();
+        assert_eq!(change.old_commit(), Some(COMMIT1));
+        assert_eq!(change.new_commit(), Some(COMMIT2));
+    }
+
+    #[test]
+    fn register_change_should_allow_callback_to_get_change() {
+        // arrange
+        let repo = TempRepository::new("git.8");
+
+        repo.write("refs/heads/main", COMMIT1);
+
+        let token = Arc::new(GitRefChangeToken::new(&repo.0).unwrap());
+        let weak = Arc::downgrade(&token);
+        let (sender, receiver) = channel();
+        let _registration = token.register_change(
+            Box::new(move |_, _| {
+                let change = weak.upgrade().and_then(|token| token.change());
+                sender.send(change).unwrap_or_default()
+            }),
+            None,
+        );
+
+        // act
+        repo.write("refs/heads/main", COMMIT2);
+
+        // assert
+        let change = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(change.unwrap().new_commit(), Some(COMMIT2));
+    }
+
+    #[test]
+    fn git_ref_change_token_should_not_change_when_other_branch_is_updated() {
+        // arrange
+        let repo = TempRepository::new("git.7");
+
+        repo.write("refs/heads/main", COMMIT1);
+
+        let token = GitRefChangeToken::new(&repo.0).unwrap();
+
+        // act
+        repo.write("refs/heads/other", COMMIT2);
+
+        // assert
+        assert!(!token.wait_timeout(Duration::from_millis(250)));
+        assert!(!token.changed());
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{ChangeToken, Registration, Subscription};
+use crate::{sync::MutexExt, ChangeToken, Registration, Subscription};
 use std::{
     any::Any,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     sync::{Arc, Mutex, Weak},
 };
 
@@ -72,35 +73,48 @@ where
 
     fn register(&self, token: TToken) {
         let this = Arc::new(self.me.clone());
-        let registration = token.register(Box::new(Self::on_changed), Some(this));
+        let registration = token.register_or_invoke(Box::new(Self::on_changed), Some(this));
 
         // only update the registration if the token hasn't
         // already changed and it doesn't require polling.
+        // a token that has already changed has invoked the
+        // callback, which has registered for the next token.
         // the old token and registration are immediately dropped
         if !token.changed() || token.must_poll() {
-            *self.registration.lock().unwrap() = (Some(token), registration);
+            let old = std::mem::replace(
+                &mut *self.registration.lock_or_recover(),
+                (Some(token), registration),
+            );
+            drop(old);
         }
     }
 
     fn on_changed(state: Option<Arc<dyn Any>>) {
-        state
-            .unwrap()
-            .downcast_ref::<Weak<Self>>()
-            .unwrap()
-            .upgrade()
-            .unwrap()
-            .on_notified()
+        let state = state.unwrap();
+
+        // the subscription may have been dropped while the change was in flight
+        if let Some(this) = state.downcast_ref::<Weak<Self>>().unwrap().upgrade() {
+            this.on_notified()
+        }
     }
 
     fn on_notified(&self) {
         let token = (self.producer)();
-        (self.consumer)(self.state.clone());
+
+        // a consumer that panics must not end the subscription so
+        // always register for the next change before resuming the panic
+        let result = catch_unwind(AssertUnwindSafe(|| (self.consumer)(self.state.clone())));
+
         self.register(token);
+
+        if let Err(panic) = result {
+            resume_unwind(panic)
+        }
     }
 }
 
 struct SubscriptionImpl<TToken, TProducer, TConsumer, TState>(
-    Arc<ChangeTokenRegistration<TToken, TProducer, TConsumer, TState>>,
+    #[allow(dead_code)] Arc<ChangeTokenRegistration<TToken, TProducer, TConsumer, TState>>,
 )
 where
     TState: 'static,
@@ -146,8 +160,8 @@ mod tests {
     use std::{
         mem::ManuallyDrop,
         sync::{
-            atomic::{AtomicBool, Ordering},
-            Arc,
+            atomic::{AtomicBool, AtomicU8, Ordering},
+            Arc, RwLock,
         },
     };
 
@@ -189,4 +203,78 @@ mod tests {
         // assert
         assert!(!fired.load(Ordering::SeqCst));
     }
+
+    #[test]
+    fn changed_should_signal_consumer_after_consumer_panics() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let count = Arc::new(AtomicU8::default());
+        let producer = token.clone();
+        let _unused = on_change(
+            move || producer.clone(),
+            |state| {
+                if state.unwrap().fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("expected");
+                }
+            },
+            Some(count.clone()),
+        );
+
+        let _ = token.try_notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn changed_should_signal_consumer_with_single_change_tokens_after_consumer_panics() {
+        // arrange
+        let token = Arc::new(RwLock::new(SharedChangeToken::<SingleChangeToken>::default()));
+        let count = Arc::new(AtomicU8::default());
+        let producer = token.clone();
+        let _unused = on_change(
+            move || producer.read().unwrap().clone(),
+            |state| {
+                if state.unwrap().fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("expected");
+                }
+            },
+            Some(count.clone()),
+        );
+        let current = std::mem::take(&mut *token.write().unwrap());
+
+        let _ = current.try_notify();
+
+        let current = std::mem::take(&mut *token.write().unwrap());
+
+        // act
+        current.notify();
+
+        // assert
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn on_change_should_signal_consumer_when_token_has_already_changed() {
+        // arrange
+        let token = SharedChangeToken::<SingleChangeToken>::default();
+        let next = Arc::new(RwLock::new(token.clone()));
+        let fired = Arc::new(AtomicBool::default());
+        let producer = next.clone();
+
+        token.notify();
+
+        // act
+        let _unused = on_change(
+            move || std::mem::take(&mut *producer.write().unwrap()),
+            |state| state.unwrap().store(true, Ordering::SeqCst),
+            Some(fired.clone()),
+        );
+
+        // assert
+        assert!(fired.load(Ordering::SeqCst));
+    }
 }
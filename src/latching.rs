@@ -0,0 +1,423 @@
+use crate::{
+    Callback, CallbackRef, ChangeToken, Changed, DefaultChangeToken, Dispatcher, NotifyError,
+    Registration, VersionedCallback,
+};
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(feature = "futures")]
+use crate::Changes;
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that may change zero or more times and
+/// remains changed until the change is acknowledged.
+///
+/// # Remarks
+///
+/// Unlike [`DefaultChangeToken`](crate::DefaultChangeToken), [`changed`](crate::ChangeToken::changed)
+/// latches after a notification and does not revert until a consumer acknowledges the change. This
+/// allows a consumer that polls the token to reliably observe changes. A callback registered with
+/// [`register_or_invoke`](crate::ChangeToken::register_or_invoke) is invoked immediately while the
+/// token has an unacknowledged change.
+#[derive(Default)]
+pub struct LatchingChangeToken {
+    inner: DefaultChangeToken,
+    acknowledged: AtomicU64,
+}
+
+impl LatchingChangeToken {
+    /// Initializes a new latching change token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes a new latching change token that invokes callbacks using the specified dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The [dispatcher](crate::Dispatcher) used to invoke registered callbacks
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`DefaultChangeToken::with_dispatcher`](crate::DefaultChangeToken::with_dispatcher)
+    /// for how callbacks are dispatched.
+    pub fn with_dispatcher(dispatcher: Arc<dyn Dispatcher>) -> Self {
+        Self {
+            inner: DefaultChangeToken::with_dispatcher(dispatcher),
+            acknowledged: AtomicU64::default(),
+        }
+    }
+
+    /// Notifies any registered callbacks of a change.
+    pub fn notify(&self) {
+        self.inner.notify()
+    }
+
+    /// Notifies any registered callbacks of a change.
+    ///
+    /// # Returns
+    ///
+    /// An [error](crate::NotifyError) containing the panics of any callbacks that panicked.
+    pub fn try_notify(&self) -> Result<(), NotifyError> {
+        self.inner.try_notify()
+    }
+
+    /// Acknowledges all changes up to and including the specified version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The [version](crate::ChangeToken::version) of the last observed change
+    ///
+    /// # Remarks
+    ///
+    /// The token remains changed if a change with a newer version has occurred.
+    pub fn acknowledge(&self, version: u64) {
+        let version = version.min(self.inner.version());
+        self.acknowledged.fetch_max(version, Ordering::SeqCst);
+    }
+
+    /// Acknowledges all changes and returns a value indicating whether any change had occurred.
+    pub fn take_changed(&self) -> bool {
+        let version = self.inner.version();
+        self.acknowledged.fetch_max(version, Ordering::SeqCst) < version
+    }
+
+    /// Registers for a callback that will be invoked with the token version when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    pub fn register_versioned(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+    ) -> Registration {
+        self.inner.register_versioned(callback, state)
+    }
+
+    /// Registers for a callback that will be invoked only the next time the token changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// The callback is unregistered as soon as it is invoked, even if the registration is still held.
+    pub fn register_once(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_once(callback, state)
+    }
+
+    /// Registers for a callback that will be invoked in priority order when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    /// * `priority` - The priority of the callback relative to other callbacks
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`DefaultChangeToken::register_with_priority`](crate::DefaultChangeToken::register_with_priority)
+    /// for the ordering guarantees.
+    pub fn register_with_priority(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+    ) -> Registration {
+        self.inner.register_with_priority(callback, state, priority)
+    }
+
+    /// Creates a future that completes when the token has an unacknowledged change.
+    ///
+    /// # Returns
+    ///
+    /// A [future](crate::Changed) that completes with the [version](crate::ChangeToken::version) of the change.
+    ///
+    /// # Remarks
+    ///
+    /// The future completes immediately if the token is [changed](crate::ChangeToken::changed).
+    /// Completing the future does not acknowledge the change.
+    pub fn wait_async(&self) -> Changed<'_> {
+        Changed::new(&self.inner, self.acknowledged.load(Ordering::SeqCst))
+    }
+
+    /// Creates a stream of the changes to the token.
+    ///
+    /// # Returns
+    ///
+    /// A [stream](crate::Changes) that yields the [version](crate::ChangeToken::version) of each change.
+    ///
+    /// # Remarks
+    ///
+    /// The first item is yielded immediately if the token is [changed](crate::ChangeToken::changed).
+    /// Yielding an item does not acknowledge the change.
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    pub fn changes(&self) -> Changes<'_> {
+        Changes::new(&self.inner, self.acknowledged.load(Ordering::SeqCst))
+    }
+}
+
+impl ChangeToken for LatchingChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.version() > self.acknowledged.load(Ordering::SeqCst)
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        // the callback is registered before the latch is checked so that a change is never
+        // missed. each version is delivered at most once so a notification that races with
+        // the check doesn't invoke the callback again for a change it has already observed
+        let callback: CallbackRef = Arc::from(callback);
+        let delivered = Arc::new(AtomicU64::default());
+        let registration = {
+            let callback = callback.clone();
+            let delivered = delivered.clone();
+            self.inner.register_versioned(
+                Box::new(move |version, state| {
+                    if delivered.fetch_max(version, Ordering::SeqCst) < version {
+                        callback(state)
+                    }
+                }),
+                state.clone(),
+            )
+        };
+        let version = self.inner.version();
+
+        if version > self.acknowledged.load(Ordering::SeqCst)
+            && delivered.fetch_max(version, Ordering::SeqCst) < version
+        {
+            callback(state);
+        }
+
+        registration
+    }
+}
+
+unsafe impl Send for LatchingChangeToken {}
+unsafe impl Sync for LatchingChangeToken {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::sync::atomic::AtomicU8;
+
+    #[test]
+    fn latching_change_token_should_be_unchanged() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(!changed);
+    }
+
+    #[test]
+    fn latching_change_token_should_remain_changed_after_notify() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(changed);
+    }
+
+    #[test]
+    fn take_changed_should_reset_changed() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+
+        // act
+        let changed = token.take_changed();
+
+        // assert
+        assert!(changed);
+        assert!(!token.changed());
+        assert!(!token.take_changed());
+    }
+
+    #[test]
+    fn acknowledge_should_not_reset_newer_changes() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+
+        let version = token.version();
+
+        token.notify();
+
+        // act
+        token.acknowledge(version);
+
+        // assert
+        assert!(token.changed());
+    }
+
+    #[test]
+    fn acknowledge_should_reset_changed() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+        token.notify();
+
+        // act
+        token.acknowledge(token.version());
+
+        // assert
+        assert!(!token.changed());
+    }
+
+    #[test]
+    fn acknowledge_should_not_suppress_future_changes() {
+        // arrange
+        let token = LatchingChangeToken::default();
+
+        token.acknowledge(u64::MAX);
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(token.changed());
+    }
+
+    #[test]
+    fn latching_change_token_should_invoke_callback_multiple_times() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = LatchingChangeToken::default();
+        let _registration = token.register(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+        token.notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn register_or_invoke_should_invoke_callback_when_token_is_changed() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+
+        // act
+        let _registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn register_or_invoke_should_not_invoke_callback_when_change_is_acknowledged() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+        token.take_changed();
+
+        // act
+        let _registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn register_or_invoke_should_invoke_callback_for_subsequent_changes() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = LatchingChangeToken::default();
+
+        token.notify();
+
+        let _registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,28 +1,96 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+// the crate prefers explicit default constructors and boolean assertions
+#![allow(clippy::default_constructed_unit_structs)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
 
+mod channel;
 mod composite;
 mod default;
+mod dispatch;
+mod env;
+mod future;
 mod global;
+mod latching;
 mod never;
+mod polling;
+mod process;
 mod shared;
 mod single;
+mod slab;
+mod sync;
+#[cfg(test)]
+mod test;
 mod token;
+mod wait;
+mod watched;
 
+pub use channel::*;
 pub use composite::*;
 pub use default::*;
+pub use dispatch::*;
+pub use env::*;
+pub use future::*;
 pub use global::*;
+pub use latching::*;
 pub use never::*;
+pub use polling::*;
+pub use process::*;
 pub use shared::*;
 pub use single::*;
+pub(crate) use slab::*;
 pub use token::*;
+pub use wait::*;
+pub use watched::*;
+
+#[cfg(feature = "fs")]
+mod config;
 
 #[cfg(feature = "fs")]
 mod file;
 
+#[cfg(feature = "fs")]
+mod git;
+
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+pub use config::*;
+
 #[cfg(feature = "fs")]
 #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
 pub use file::*;
 
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+pub use git::*;
+
+#[cfg(all(target_os = "linux", feature = "eventfd"))]
+mod eventfd;
+
+#[cfg(all(target_os = "linux", feature = "eventfd"))]
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "eventfd"))))]
+pub use eventfd::*;
+
+#[cfg(all(unix, feature = "signal"))]
+mod signal;
+
+#[cfg(all(unix, feature = "signal"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "signal"))))]
+pub use signal::*;
+
+#[cfg(all(unix, feature = "socket"))]
+mod socket;
+
+#[cfg(all(unix, feature = "socket"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "socket"))))]
+pub use socket::*;
+
+#[cfg(feature = "tokio")]
+mod runtime;
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use runtime::*;
+
 /// Defines the behavior of an opaque subscription.
 pub trait Subscription {}
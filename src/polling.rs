@@ -0,0 +1,161 @@
+use crate::{Callback, ChangeToken, Registration, SingleChangeToken};
+use std::{
+    any::Any,
+    sync::{Arc, Weak},
+    thread,
+    time::Duration,
+};
+
+// the interval at which a token that must be polled is checked for changes
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+type Probe = dyn Fn() -> bool + Send + Sync;
+
+struct Polled {
+    token: SingleChangeToken,
+    probe: Box<Probe>,
+}
+
+impl Polled {
+    fn check(&self) -> bool {
+        if (self.probe)() {
+            // a callback that panics must not end polling
+            self.token.try_notify().ok();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn run(polled: Weak<Polled>, interval: Duration) {
+        loop {
+            thread::sleep(interval);
+
+            match polled.upgrade() {
+                Some(polled) if !polled.check() => continue,
+                _ => break,
+            }
+        }
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes when a polled condition is met.
+///
+/// # Remarks
+///
+/// The condition is checked periodically on a dedicated thread, which invokes the registered callbacks.
+/// [`changed`](crate::ChangeToken::changed) does not check the condition; it only indicates whether the
+/// thread has observed the change. Like a [`FileChangeToken`](crate::FileChangeToken), the token changes at most once; use
+/// [`on_change`](crate::on_change) to continue observing changes. The thread exits after the token
+/// changes or within one interval of the token being dropped.
+pub struct PollingChangeToken {
+    inner: Arc<Polled>,
+}
+
+impl PollingChangeToken {
+    /// Initializes a new polling change token that is checked every 100 milliseconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `probe` - The function that indicates whether a change has occurred
+    pub fn new<F: Fn() -> bool + Send + Sync + 'static>(probe: F) -> Self {
+        Self::with_interval(probe, POLL_INTERVAL)
+    }
+
+    /// Initializes a new polling change token that is checked at the specified interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `probe` - The function that indicates whether a change has occurred
+    /// * `interval` - The interval at which `probe` is called
+    pub fn with_interval<F: Fn() -> bool + Send + Sync + 'static>(
+        probe: F,
+        interval: Duration,
+    ) -> Self {
+        let inner = Arc::new(Polled {
+            token: SingleChangeToken::default(),
+            probe: Box::new(probe),
+        });
+        let polled = Arc::downgrade(&inner);
+
+        thread::spawn(move || Polled::run(polled, interval));
+
+        Self { inner }
+    }
+}
+
+impl ChangeToken for PollingChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.token.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.token.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.token.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.token.register_or_invoke(callback, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Wait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn polling_change_token_should_be_unchanged_until_probe_succeeds() {
+        // arrange
+        let token = PollingChangeToken::new(|| false);
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(!changed);
+    }
+
+    #[test]
+    fn changed_should_not_check_probe() {
+        // arrange
+        let flag = Arc::new(AtomicBool::default());
+        let probe = flag.clone();
+        let token = PollingChangeToken::with_interval(
+            move || probe.load(Ordering::SeqCst),
+            Duration::from_secs(60),
+        );
+
+        flag.store(true, Ordering::SeqCst);
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(!changed);
+        assert_eq!(token.version(), 0);
+    }
+
+    #[test]
+    fn polling_change_token_should_invoke_callback_when_probe_succeeds() {
+        // arrange
+        let flag = Arc::new(AtomicBool::default());
+        let probe = flag.clone();
+        let token = PollingChangeToken::with_interval(
+            move || probe.load(Ordering::SeqCst),
+            Duration::from_millis(10),
+        );
+
+        // act
+        flag.store(true, Ordering::SeqCst);
+
+        // assert
+        assert!(token.wait_timeout(Duration::from_secs(5)));
+        assert!(token.changed());
+    }
+}
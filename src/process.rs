@@ -0,0 +1,232 @@
+use crate::{sync::MutexExt, Callback, ChangeToken, PollingChangeToken, Registration};
+use std::{
+    any::Any,
+    process::{Child, ExitStatus},
+    sync::{Arc, Mutex},
+};
+
+/// Represents a callback function invoked with the exit status of a process.
+pub type ExitCallback = Box<dyn Fn(Option<ExitStatus>, Option<Arc<dyn Any>>) + Send + Sync>;
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes when a process exits.
+///
+/// # Remarks
+///
+/// The process is checked using a [`PollingChangeToken`](crate::PollingChangeToken), so registered
+/// notifications always occur on another thread and the token changes at most once.
+pub struct ProcessChangeToken {
+    inner: PollingChangeToken,
+    status: Arc<Mutex<Option<ExitStatus>>>,
+}
+
+impl ProcessChangeToken {
+    /// Initializes a new process change token for a child process.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The [child process](std::process::Child) to watch
+    ///
+    /// # Remarks
+    ///
+    /// The token owns the child process and reaps it when it exits. Dropping the token
+    /// does not kill the child process.
+    pub fn new(child: Child) -> Self {
+        let status = Arc::new(Mutex::new(None));
+        let exited = status.clone();
+        let child = Mutex::new(child);
+        let inner = PollingChangeToken::new(move || match child.lock_or_recover().try_wait() {
+            Ok(Some(status)) => {
+                *exited.lock_or_recover() = Some(status);
+                true
+            }
+            // a process that cannot be waited on is never observed to exit
+            Ok(None) | Err(_) => false,
+        });
+
+        Self { inner, status }
+    }
+
+    /// Initializes a new process change token for the specified process identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - The identifier of the process to watch
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the process cannot be watched.
+    ///
+    /// # Remarks
+    ///
+    /// The process is watched using a Linux pidfd, which requires Linux 5.3 or later. The process
+    /// does not need to be a child of the current process. The [exit status](Self::exit_status) is
+    /// not available because the process is not reaped by the token.
+    #[cfg(all(target_os = "linux", feature = "pidfd"))]
+    #[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "pidfd"))))]
+    pub fn from_pid(pid: u32) -> std::io::Result<Self> {
+        let pidfd = pidfd::PidFd::open(pid)?;
+        let inner = PollingChangeToken::new(move || pidfd.exited());
+
+        Ok(Self {
+            inner,
+            status: Arc::default(),
+        })
+    }
+
+    /// Gets the exit status of the process, if it has exited.
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        if self.inner.changed() {
+            *self.status.lock_or_recover()
+        } else {
+            None
+        }
+    }
+
+    /// Registers for a callback that will be invoked with the exit status when the process exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the [exit status](std::process::ExitStatus), if available
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// If the process has already exited, the callback is invoked immediately.
+    pub fn register_exit(
+        &self,
+        callback: ExitCallback,
+        state: Option<Arc<dyn Any>>,
+    ) -> Registration {
+        let status = self.status.clone();
+        self.inner.register_or_invoke(
+            Box::new(move |state| callback(*status.lock_or_recover(), state)),
+            state,
+        )
+    }
+}
+
+impl ChangeToken for ProcessChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "pidfd"))]
+mod pidfd {
+    use std::{
+        io::{Error, Result},
+        os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    };
+
+    // represents a file descriptor that refers to a process. it
+    // becomes readable when the process it refers to exits
+    pub(super) struct PidFd(OwnedFd);
+
+    impl PidFd {
+        pub(super) fn open(pid: u32) -> Result<Self> {
+            let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+
+            if fd < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(Self(unsafe { OwnedFd::from_raw_fd(fd as i32) }))
+            }
+        }
+
+        pub(super) fn exited(&self) -> bool {
+            let mut fd = libc::pollfd {
+                fd: self.0.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            unsafe { libc::poll(&mut fd, 1, 0) > 0 }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+
+    use super::*;
+    #[cfg(all(target_os = "linux", feature = "pidfd"))]
+    use crate::Wait;
+    use std::{
+        process::{Command, Stdio},
+        sync::mpsc::channel,
+        time::Duration,
+    };
+
+    fn spawn(script: &str) -> Child {
+        Command::new("sh").arg("-c").arg(script).spawn().unwrap()
+    }
+
+    #[test]
+    fn process_change_token_should_be_unchanged_while_process_is_running() {
+        // arrange
+        // the process exits when the token is dropped and its input is closed
+        let child = Command::new("cat").stdin(Stdio::piped()).spawn().unwrap();
+        let token = ProcessChangeToken::new(child);
+
+        // act
+        let changed = token.changed();
+
+        // assert
+        assert!(!changed);
+        assert_eq!(token.exit_status(), None);
+    }
+
+    #[test]
+    fn register_exit_should_receive_exit_status() {
+        // arrange
+        let token = ProcessChangeToken::new(spawn("exit 3"));
+        let (sender, receiver) = channel();
+        let _registration = token.register_exit(
+            Box::new(move |status, _| sender.send(status).unwrap_or_default()),
+            None,
+        );
+
+        // act
+        let status = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // assert
+        assert_eq!(status.and_then(|s| s.code()), Some(3));
+        assert_eq!(token.exit_status().and_then(|s| s.code()), Some(3));
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "pidfd"))]
+    fn process_change_token_should_change_when_process_identifier_exits() {
+        // arrange
+        let mut child = spawn("sleep 5");
+        let token = ProcessChangeToken::from_pid(child.id()).unwrap();
+        let unchanged = !token.changed();
+
+        // act
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // assert
+        assert!(unchanged);
+        assert!(token.wait_timeout(Duration::from_secs(5)));
+        assert_eq!(token.exit_status(), None);
+    }
+}
@@ -0,0 +1,656 @@
+use crate::{
+    polling::POLL_INTERVAL, Callback, ChangeToken, DefaultChangeToken, Registration, Subscription,
+    WaitAny,
+};
+use std::{
+    any::Any,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    sync::{watch, Notify},
+    task::JoinHandle,
+    time::{interval_at, sleep, Instant, MissedTickBehavior},
+};
+
+// represents a task that is aborted when it is dropped
+struct Task(JoinHandle<()>);
+
+impl Task {
+    fn spawn<F: Future<Output = ()> + Send + 'static>(future: F) -> Self {
+        Self(Handle::current().spawn(future))
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.0.abort()
+    }
+}
+
+/// Creates a [watch channel](tokio::sync::watch) that is signaled whenever a [`ChangeToken`](crate::ChangeToken) changes.
+///
+/// # Arguments
+///
+/// * `token` - The [change token](crate::ChangeToken) to observe
+///
+/// # Returns
+///
+/// A tuple containing the [receiver](tokio::sync::watch::Receiver) and the change token
+/// [registration](crate::Registration). When the registration is dropped, the channel is no longer signaled.
+///
+/// # Remarks
+///
+/// The value of the channel is the number of changes observed since the channel was created.
+pub fn watch_changes<T: ChangeToken + ?Sized>(token: &T) -> (watch::Receiver<u64>, Registration) {
+    let (sender, receiver) = watch::channel(0);
+    let registration = token.register_or_invoke(
+        Box::new(move |_| {
+            sender.send_modify(|changes| *changes += 1);
+        }),
+        None,
+    );
+    (receiver, registration)
+}
+
+/// Signals a [`Notify`](tokio::sync::Notify) whenever a [`ChangeToken`](crate::ChangeToken) changes.
+///
+/// # Arguments
+///
+/// * `token` - The [change token](crate::ChangeToken) to observe
+/// * `notify` - The [`Notify`](tokio::sync::Notify) to signal
+///
+/// # Returns
+///
+/// The change token [registration](crate::Registration). When it is dropped, `notify` is no longer signaled.
+///
+/// # Remarks
+///
+/// Every task waiting on `notify` is woken when the token changes. A change that occurs
+/// while no task is waiting is not observed. Use [`watch_changes`] to observe every change.
+pub fn notify_on_change<T: ChangeToken + ?Sized>(token: &T, notify: Arc<Notify>) -> Registration {
+    token.register_or_invoke(Box::new(move |_| notify.notify_waiters()), None)
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes whenever a [watch channel](tokio::sync::watch) changes.
+///
+/// # Remarks
+///
+/// Registered callbacks are invoked on a task spawned on the current tokio runtime.
+pub struct WatchChangeToken {
+    inner: Arc<DefaultChangeToken>,
+    _task: Task,
+}
+
+impl WatchChangeToken {
+    /// Initializes a new watch change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - The [receiver](tokio::sync::watch::Receiver) to observe
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub fn new<T: Send + Sync + 'static>(mut receiver: watch::Receiver<T>) -> Self {
+        let inner = Arc::new(DefaultChangeToken::new());
+        let token = inner.clone();
+        let task = Task::spawn(async move {
+            // a callback that panics must not end the task
+            while receiver.changed().await.is_ok() {
+                token.try_notify().ok();
+            }
+        });
+
+        Self { inner, _task: task }
+    }
+}
+
+impl ChangeToken for WatchChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes whenever a [`Notify`](tokio::sync::Notify) is signaled.
+///
+/// # Remarks
+///
+/// Registered callbacks are invoked on a task spawned on the current tokio runtime.
+pub struct NotifyChangeToken {
+    inner: Arc<DefaultChangeToken>,
+    _task: Task,
+}
+
+impl NotifyChangeToken {
+    /// Initializes a new notify change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `notify` - The [`Notify`](tokio::sync::Notify) to observe
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub fn new(notify: Arc<Notify>) -> Self {
+        let inner = Arc::new(DefaultChangeToken::new());
+        let token = inner.clone();
+        let task = Task::spawn(async move {
+            // a callback that panics must not end the task
+            loop {
+                notify.notified().await;
+                token.try_notify().ok();
+            }
+        });
+
+        Self { inner, _task: task }
+    }
+}
+
+impl ChangeToken for NotifyChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) backed by a tokio timer.
+///
+/// # Remarks
+///
+/// Registered callbacks are invoked on a task spawned on the current tokio runtime.
+pub struct TimerChangeToken {
+    inner: Arc<DefaultChangeToken>,
+    _task: Task,
+}
+
+impl TimerChangeToken {
+    /// Initializes a new timer change token that changes once after the specified delay.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The amount of time to wait before the token changes
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub fn new(delay: Duration) -> Self {
+        let inner = Arc::new(DefaultChangeToken::once(None));
+        let token = inner.clone();
+        let task = Task::spawn(async move {
+            sleep(delay).await;
+            token.notify();
+        });
+
+        Self { inner, _task: task }
+    }
+
+    /// Initializes a new timer change token that changes every period.
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The amount of time between changes
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime or `period` is zero.
+    ///
+    /// # Remarks
+    ///
+    /// The first change occurs after one period has elapsed. If a change is delayed,
+    /// for example because callbacks take longer than the period, missed changes are skipped.
+    pub fn periodic(period: Duration) -> Self {
+        let inner = Arc::new(DefaultChangeToken::new());
+        let token = inner.clone();
+        let task = Task::spawn(async move {
+            let mut timer = interval_at(Instant::now() + period, period);
+
+            timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            // a callback that panics must not end the task
+            loop {
+                timer.tick().await;
+                token.try_notify().ok();
+            }
+        });
+
+        Self { inner, _task: task }
+    }
+}
+
+impl ChangeToken for TimerChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
+}
+
+struct TaskSubscription(#[allow(dead_code)] Task);
+
+// waits for the token to change after the specified version was observed. a token that
+// must be polled is checked using a tokio interval rather than a helper thread
+async fn changed_since(token: &dyn ChangeToken, version: u64) {
+    let tokens = [token];
+    let mut changed = WaitAny::since(&tokens, vec![version]).without_ticks();
+    let mut timer = interval_at(Instant::now() + POLL_INTERVAL, POLL_INTERVAL);
+
+    timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    poll_fn(|cx| loop {
+        if Pin::new(&mut changed).poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+
+        if !token.must_poll() || timer.poll_tick(cx).is_pending() {
+            return Poll::Pending;
+        }
+    })
+    .await
+}
+
+impl Subscription for TaskSubscription {}
+
+/// Registers an asynchronous consumer to be invoked by a tokio task whenever the [token](crate::ChangeToken) produced changes.
+///
+/// # Arguments
+///
+/// * `producer` - The function that produces the [change token](crate::ChangeToken)
+/// * `consumer` - The asynchronous function that is called when the change token changes
+/// * `state` - The optional state supplied to the consumer
+///
+/// # Returns
+///
+/// An opaque [subscription](crate::Subscription). When it is dropped, the task is aborted,
+/// the producer will no longer be polled, and the consumer will no longer be notified.
+///
+/// # Remarks
+///
+/// This is the asynchronous counterpart of [`on_change`](crate::on_change). The consumer is
+/// awaited on a task spawned on the current tokio runtime rather than invoked by the change
+/// token. The first token is produced before this function returns and the next token is always
+/// produced before the consumer is invoked so that a change which occurs before the task runs or
+/// while the consumer is running is never missed. A token that [must be polled](crate::ChangeToken::must_poll)
+/// is checked every 100 milliseconds by the task. A consumer that panics does not end the subscription.
+///
+/// # Panics
+///
+/// Panics if called outside the context of a tokio runtime.
+pub fn spawn_on_change<TToken, TProducer, TConsumer, TFuture, TState>(
+    producer: TProducer,
+    consumer: TConsumer,
+    state: Option<Arc<TState>>,
+) -> impl Subscription
+where
+    TState: Send + Sync + 'static,
+    TToken: ChangeToken + 'static,
+    TProducer: Fn() -> TToken + Send + 'static,
+    TConsumer: Fn(Option<Arc<TState>>) -> TFuture + Send + 'static,
+    TFuture: Future<Output = ()> + Send + 'static,
+{
+    // the version is captured as soon as a token is produced, rather than when the task
+    // waits on it, so a change that occurs before the wait begins is still observed
+    let mut token = producer();
+    let mut version = token.version();
+
+    TaskSubscription(Task::spawn(async move {
+        loop {
+            changed_since(&token, version).await;
+            token = producer();
+            version = token.version();
+
+            // the consumer runs on its own task, which isolates a panic
+            // and is aborted if the subscription is dropped while it runs
+            let mut consumer = Task::spawn(consumer(state.clone()));
+            (&mut consumer.0).await.ok();
+        }
+    }))
+}
+
+#[cfg(feature = "fs")]
+mod file {
+    use super::Task;
+    use crate::{Callback, ChangeToken, Registration, SingleChangeToken};
+    use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::{any::Any, path::Path, sync::Arc};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    /// Represents a [`ChangeToken`](crate::ChangeToken) for a file that is driven by a tokio runtime.
+    ///
+    /// # Remarks
+    ///
+    /// Unlike [`FileChangeToken`](crate::FileChangeToken), no thread waits for the events of the
+    /// token; they are received by a task spawned on the current tokio runtime, which also invokes
+    /// the registered callbacks. The underlying watcher still runs its own event loop thread.
+    pub struct AsyncFileChangeToken {
+        _watcher: RecommendedWatcher,
+        _task: Task,
+        inner: Arc<SingleChangeToken>,
+    }
+
+    impl AsyncFileChangeToken {
+        /// Initializes a new asynchronous file change token.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - The [path](std::path::Path) of the file to watch for changes
+        ///
+        /// # Panics
+        ///
+        /// Panics if called outside the context of a tokio runtime or the file cannot be watched.
+        pub fn new<T: AsRef<Path>>(path: T) -> Self {
+            let inner = Arc::new(SingleChangeToken::default());
+            let token = inner.clone();
+            let (sender, mut receiver) = unbounded_channel::<Event>();
+            let mut watcher = RecommendedWatcher::new(
+                move |event: notify::Result<Event>| {
+                    if let Ok(event) = event {
+                        sender.send(event).ok();
+                    }
+                },
+                Config::default(),
+            )
+            .unwrap();
+            let task = Task::spawn(async move {
+                while let Some(event) = receiver.recv().await {
+                    if event.kind.is_modify() {
+                        token.notify();
+                        break;
+                    }
+                }
+            });
+
+            watcher
+                .watch(path.as_ref(), RecursiveMode::NonRecursive)
+                .unwrap();
+
+            Self {
+                _watcher: watcher,
+                _task: task,
+                inner,
+            }
+        }
+    }
+
+    impl ChangeToken for AsyncFileChangeToken {
+        fn changed(&self) -> bool {
+            self.inner.changed()
+        }
+
+        fn version(&self) -> u64 {
+            self.inner.version()
+        }
+
+        fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+            self.inner.register(callback, state)
+        }
+
+        fn register_or_invoke(
+            &self,
+            callback: Callback,
+            state: Option<Arc<dyn Any>>,
+        ) -> Registration {
+            self.inner.register_or_invoke(callback, state)
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+pub use file::*;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::wait_any_async;
+    use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+    use tokio::time::timeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    struct PolledChangeToken(Arc<AtomicBool>);
+
+    impl ChangeToken for PolledChangeToken {
+        fn changed(&self) -> bool {
+            self.0.load(Ordering::SeqCst)
+        }
+
+        fn must_poll(&self) -> bool {
+            true
+        }
+
+        fn register(&self, _callback: Callback, _state: Option<Arc<dyn Any>>) -> Registration {
+            Registration::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn watch_changes_should_signal_receiver() {
+        // arrange
+        let token = DefaultChangeToken::new();
+        let (mut receiver, _registration) = watch_changes(&token);
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        timeout(TIMEOUT, receiver.changed()).await.unwrap().unwrap();
+        assert_eq!(*receiver.borrow(), 2);
+    }
+
+    #[tokio::test]
+    async fn notify_on_change_should_wake_waiting_task() {
+        // arrange
+        let token = DefaultChangeToken::new();
+        let notify = Arc::new(Notify::new());
+        let _registration = notify_on_change(&token, notify.clone());
+        let notified = notify.notified();
+
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        // act
+        token.notify();
+
+        // assert
+        timeout(TIMEOUT, notified).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn watch_change_token_should_change_when_channel_changes() {
+        // arrange
+        let (sender, _receiver) = watch::channel(0);
+        let token = WatchChangeToken::new(sender.subscribe());
+        let tokens: [&dyn ChangeToken; 1] = [&token];
+        let changed = wait_any_async(&tokens);
+
+        // act
+        sender.send(1).unwrap();
+
+        // assert
+        timeout(TIMEOUT, changed).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn notify_change_token_should_change_when_notified() {
+        // arrange
+        let notify = Arc::new(Notify::new());
+        let token = NotifyChangeToken::new(notify.clone());
+        let tokens: [&dyn ChangeToken; 1] = [&token];
+        let changed = wait_any_async(&tokens);
+
+        // act
+        notify.notify_one();
+
+        // assert
+        timeout(TIMEOUT, changed).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn timer_change_token_should_change_after_delay() {
+        // arrange
+        let token = TimerChangeToken::new(Duration::from_millis(10));
+
+        // act
+        timeout(TIMEOUT, wait_any_async(&[&token])).await.unwrap();
+
+        // assert
+        assert!(token.changed());
+    }
+
+    #[tokio::test]
+    async fn periodic_timer_change_token_should_change_repeatedly() {
+        // arrange
+        let token = TimerChangeToken::periodic(Duration::from_millis(10));
+
+        // act
+        timeout(TIMEOUT, wait_any_async(&[&token])).await.unwrap();
+        timeout(TIMEOUT, wait_any_async(&[&token])).await.unwrap();
+
+        // assert
+        assert!(token.version() >= 2);
+    }
+
+    #[tokio::test]
+    async fn spawn_on_change_should_invoke_consumer() {
+        // arrange
+        let token = crate::SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let counter = Arc::new(AtomicU8::default());
+        let notify = Arc::new(Notify::new());
+        let consumed = notify.clone();
+        let _subscription = spawn_on_change(
+            move || producer.clone(),
+            move |state| {
+                let consumed = consumed.clone();
+                async move {
+                    state.unwrap().fetch_add(1, Ordering::SeqCst);
+                    consumed.notify_one();
+                }
+            },
+            Some(counter.clone()),
+        );
+
+        // act
+        token.notify();
+
+        // assert
+        timeout(TIMEOUT, notify.notified()).await.unwrap();
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn spawn_on_change_should_not_miss_change_while_consumer_runs() {
+        // arrange
+        let token = crate::SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let changer = token.clone();
+        let counter = Arc::new(AtomicU8::default());
+        let notify = Arc::new(Notify::new());
+        let consumed = notify.clone();
+        let _subscription = spawn_on_change(
+            move || producer.clone(),
+            move |state| {
+                let consumed = consumed.clone();
+                let changer = changer.clone();
+                async move {
+                    // the first invocation changes the token again
+                    if state.unwrap().fetch_add(1, Ordering::SeqCst) == 0 {
+                        changer.notify();
+                    }
+
+                    consumed.notify_one();
+                }
+            },
+            Some(counter.clone()),
+        );
+
+        // act
+        token.notify();
+
+        // assert
+        timeout(TIMEOUT, notify.notified()).await.unwrap();
+        timeout(TIMEOUT, notify.notified()).await.unwrap();
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn spawn_on_change_should_poll_token_that_must_be_polled() {
+        // arrange
+        let flag = Arc::new(AtomicBool::default());
+        let changed = flag.clone();
+        let notify = Arc::new(Notify::new());
+        let consumed = notify.clone();
+        let _subscription = spawn_on_change(
+            move || PolledChangeToken(changed.clone()),
+            move |state| {
+                let consumed = consumed.clone();
+                async move {
+                    state.unwrap().store(false, Ordering::SeqCst);
+                    consumed.notify_one();
+                }
+            },
+            Some(flag.clone()),
+        );
+
+        // act
+        flag.store(true, Ordering::SeqCst);
+
+        // assert
+        timeout(TIMEOUT, notify.notified()).await.unwrap();
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn async_file_change_token_should_change_when_file_changes() {
+        // arrange
+        let path = std::env::temp_dir().join("test.async.1.txt");
+
+        std::fs::write(&path, "original").unwrap();
+
+        let token = AsyncFileChangeToken::new(&path);
+        let tokens: [&dyn ChangeToken; 1] = [&token];
+        let changed = wait_any_async(&tokens);
+
+        // act
+        std::fs::write(&path, "updated").unwrap();
+
+        // assert
+        let result = timeout(TIMEOUT, changed).await;
+        drop(token);
+        std::fs::remove_file(&path).ok();
+        assert!(result.is_ok());
+    }
+}
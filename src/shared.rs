@@ -48,9 +48,17 @@ impl<T: ChangeToken> ChangeToken for SharedChangeToken<T> {
         self.inner.must_poll()
     }
 
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
         self.inner.register(callback, state)
     }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
 }
 
 impl<T: ChangeToken> AsRef<T> for SharedChangeToken<T> {
@@ -0,0 +1,178 @@
+use crate::{Callback, ChangeToken, Registration, SingleChangeToken};
+use signal_hook::{
+    consts::FORBIDDEN,
+    iterator::{Handle, Signals},
+};
+use std::{
+    any::Any,
+    io::{Error, ErrorKind, Result},
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes when a Unix signal is delivered.
+///
+/// # Remarks
+///
+/// Registered notifications always occur on another thread. Any number of tokens may observe the
+/// same signal at the same time and every token changes when the signal is delivered. The token
+/// changes at most once, which allows it to be combined with a [`FileChangeToken`](crate::FileChangeToken)
+/// in a [`CompositeChangeToken`](crate::CompositeChangeToken).
+pub struct SignalChangeToken {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+    inner: Arc<SingleChangeToken>,
+}
+
+impl SignalChangeToken {
+    /// Initializes a new signal change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - The signal to observe, such as `SIGHUP`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signal cannot be observed. Use [`try_new`](Self::try_new) to handle the error instead.
+    pub fn new(signal: i32) -> Self {
+        Self::try_new(signal).unwrap()
+    }
+
+    /// Initializes a new signal change token.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - The signal to observe, such as `SIGHUP`
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the signal cannot be observed. The signals
+    /// that cannot be observed include `SIGKILL`, `SIGSTOP`, `SIGILL`, `SIGFPE` and `SIGSEGV`.
+    pub fn try_new(signal: i32) -> Result<Self> {
+        if FORBIDDEN.contains(&signal) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Signal {} cannot be observed", signal),
+            ));
+        }
+
+        let mut signals = Signals::new([signal])?;
+        let handle = signals.handle();
+        let inner = Arc::new(SingleChangeToken::default());
+        let handler = inner.clone();
+
+        // the signal is no longer observed once the iterator is dropped
+        let thread = thread::spawn(move || {
+            if signals.forever().next().is_some() {
+                handler.notify()
+            }
+        });
+
+        Ok(Self {
+            handle,
+            thread: Some(thread),
+            inner,
+        })
+    }
+}
+
+impl ChangeToken for SignalChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
+}
+
+impl Drop for SignalChangeToken {
+    fn drop(&mut self) {
+        self.handle.close();
+
+        // the token might be dropped by one of its own callbacks,
+        // which must not wait on the thread that is invoking it
+        if let Some(thread) = self.thread.take() {
+            if thread.thread().id() != thread::current().id() {
+                thread.join().ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{CompositeChangeToken, DefaultChangeToken, Wait};
+    use signal_hook::{
+        consts::{SIGKILL, SIGUSR1, SIGUSR2, SIGWINCH},
+        low_level::raise,
+    };
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn signal_change_token_should_change_when_signal_is_delivered() {
+        // arrange
+        let token = SignalChangeToken::new(SIGUSR1);
+
+        // act
+        raise(SIGUSR1).unwrap();
+
+        // assert
+        assert!(token.wait_timeout(TIMEOUT));
+        assert!(token.changed());
+    }
+
+    #[test]
+    fn every_signal_change_token_should_change_when_signal_is_delivered() {
+        // arrange
+        let token1 = SignalChangeToken::new(SIGUSR2);
+        let token2 = SignalChangeToken::new(SIGUSR2);
+
+        // act
+        raise(SIGUSR2).unwrap();
+
+        // assert
+        assert!(token1.wait_timeout(TIMEOUT));
+        assert!(token2.wait_timeout(TIMEOUT));
+    }
+
+    #[test]
+    fn composite_change_token_should_change_when_signal_is_delivered() {
+        // arrange
+        let tokens: Vec<Box<dyn ChangeToken>> = vec![
+            Box::new(DefaultChangeToken::default()),
+            Box::new(SignalChangeToken::new(SIGWINCH)),
+        ];
+        let token = CompositeChangeToken::new(tokens.into_iter());
+
+        // act
+        raise(SIGWINCH).unwrap();
+
+        // assert
+        assert!(token.wait_timeout(TIMEOUT));
+    }
+
+    #[test]
+    fn try_new_should_return_error_when_signal_cannot_be_observed() {
+        // arrange
+        let signal = SIGKILL;
+
+        // act
+        let result = SignalChangeToken::try_new(signal);
+
+        // assert
+        assert!(result.is_err());
+    }
+}
@@ -1,6 +1,12 @@
-use crate::{Callback, ChangeToken, DefaultChangeToken, Registration};
+use crate::{
+    Callback, ChangeToken, Changed, DefaultChangeToken, Dispatcher, NotifyError, Registration,
+    VersionedCallback,
+};
 use std::{any::Any, sync::Arc};
 
+#[cfg(feature = "futures")]
+use crate::Changes;
+
 /// Represents a [`ChangeToken`](crate::ChangeToken) that changes at most once.
 pub struct SingleChangeToken {
     inner: DefaultChangeToken,
@@ -12,6 +18,22 @@ impl SingleChangeToken {
         Self::default()
     }
 
+    /// Initializes a new single change token that invokes callbacks using the specified dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The [dispatcher](crate::Dispatcher) used to invoke registered callbacks
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`DefaultChangeToken::with_dispatcher`](crate::DefaultChangeToken::with_dispatcher)
+    /// for how callbacks are dispatched.
+    pub fn with_dispatcher(dispatcher: Arc<dyn Dispatcher>) -> Self {
+        Self {
+            inner: DefaultChangeToken::once(Some(dispatcher)),
+        }
+    }
+
     /// Notifies any registered callbacks of a change.
     ///
     /// # Remarks
@@ -21,12 +43,91 @@ impl SingleChangeToken {
     pub fn notify(&self) {
         self.inner.notify()
     }
+
+    /// Notifies any registered callbacks of a change.
+    ///
+    /// # Returns
+    ///
+    /// An [error](crate::NotifyError) containing the panics of any callbacks that panicked.
+    pub fn try_notify(&self) -> Result<(), NotifyError> {
+        self.inner.try_notify()
+    }
+
+    /// Registers for a callback that will be invoked with the token version when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    pub fn register_versioned(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+    ) -> Registration {
+        self.inner.register_versioned(callback, state)
+    }
+
+    /// Registers for a callback that will be invoked in priority order when the token has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the version of the change
+    /// * `state` - The optional state provided to the callback, if any
+    /// * `priority` - The priority of the callback relative to other callbacks
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// Refer to [`DefaultChangeToken::register_with_priority`](crate::DefaultChangeToken::register_with_priority)
+    /// for the ordering guarantees.
+    pub fn register_with_priority(
+        &self,
+        callback: VersionedCallback,
+        state: Option<Arc<dyn Any>>,
+        priority: i32,
+    ) -> Registration {
+        self.inner.register_with_priority(callback, state, priority)
+    }
+
+    /// Creates a future that completes when the token has changed.
+    ///
+    /// # Returns
+    ///
+    /// A [future](crate::Changed) that completes with the [version](crate::ChangeToken::version) of the change.
+    ///
+    /// # Remarks
+    ///
+    /// The future completes immediately if the token has already changed.
+    pub fn wait_async(&self) -> Changed<'_> {
+        self.inner.wait_async()
+    }
+
+    /// Creates a stream of the changes to the token.
+    ///
+    /// # Returns
+    ///
+    /// A [stream](crate::Changes) that yields the [version](crate::ChangeToken::version) of the
+    /// change and then ends.
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    pub fn changes(&self) -> Changes<'_> {
+        self.inner.changes()
+    }
 }
 
 impl Default for SingleChangeToken {
     fn default() -> Self {
         Self {
-            inner: DefaultChangeToken::once(),
+            inner: DefaultChangeToken::once(None),
         }
     }
 }
@@ -36,9 +137,17 @@ impl ChangeToken for SingleChangeToken {
         self.inner.changed()
     }
 
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
         self.inner.register(callback, state)
     }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register_or_invoke(callback, state)
+    }
 }
 
 unsafe impl Send for SingleChangeToken {}
@@ -48,6 +157,7 @@ unsafe impl Sync for SingleChangeToken {}
 mod tests {
 
     use super::*;
+    use crate::ThreadDispatcher;
     use std::sync::{
         atomic::{AtomicU8, Ordering},
         Arc,
@@ -115,4 +225,122 @@ mod tests {
         // assert
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn single_change_token_should_not_increment_version_more_than_once() {
+        // arrange
+        let token = SingleChangeToken::default();
+
+        token.notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(token.version(), 1);
+    }
+
+    #[test]
+    fn registration_should_not_be_active_after_notify() {
+        // arrange
+        let token = SingleChangeToken::default();
+        let registration = token.register(Box::new(|_| {}), None);
+
+        // act
+        token.notify();
+
+        // assert
+        assert!(registration.fired());
+        assert!(!registration.is_active());
+    }
+
+    #[test]
+    fn single_change_token_should_be_changed_when_callback_panics() {
+        // arrange
+        let token = SingleChangeToken::default();
+        let _registration = token.register(Box::new(|_| panic!("expected")), None);
+
+        // act
+        let result = token.try_notify();
+
+        // assert
+        assert!(result.is_err());
+        assert!(token.changed());
+    }
+
+    #[test]
+    fn single_change_token_should_invoke_callback_using_dispatcher() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let dispatcher = Arc::new(ThreadDispatcher::new());
+        let token = SingleChangeToken::with_dispatcher(dispatcher.clone());
+        let invocations = counter.clone();
+        let _registration = token.register(
+            Box::new(move |_| {
+                invocations.fetch_add(1, Ordering::SeqCst);
+            }),
+            None,
+        );
+
+        token.notify();
+        drop(token);
+
+        // act
+        drop(Arc::try_unwrap(dispatcher).ok().unwrap());
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn register_or_invoke_should_invoke_callback_when_changed() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = SingleChangeToken::default();
+
+        token.notify();
+
+        // act
+        let registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+
+        // assert
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(registration.fired());
+        assert!(!registration.is_active());
+    }
+
+    #[test]
+    fn register_or_invoke_should_invoke_callback_once_when_unchanged() {
+        // arrange
+        let counter = Arc::new(AtomicU8::default());
+        let token = SingleChangeToken::default();
+        let _registration = token.register_or_invoke(
+            Box::new(|state| {
+                state
+                    .unwrap()
+                    .downcast_ref::<AtomicU8>()
+                    .unwrap()
+                    .fetch_add(1, Ordering::SeqCst);
+            }),
+            Some(counter.clone()),
+        );
+        let invoked = counter.load(Ordering::SeqCst);
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        assert_eq!(invoked, 0);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,230 @@
+// represents a generational key to a value in a slab. the generation ensures that
+// a stale key can never remove a value that has since reused the same index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Key {
+    index: usize,
+    generation: u64,
+}
+
+enum Entry<T> {
+    Occupied(u64, T),
+    Vacant(u64, Option<usize>),
+}
+
+// represents a collection of values with O(1) insertion and removal by key
+pub(crate) struct Slab<T> {
+    entries: Vec<Entry<T>>,
+    next: Option<usize>,
+    len: usize,
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            next: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> Slab<T> {
+    // gets the key that the next inserted value will have
+    pub(crate) fn vacant_key(&self) -> Key {
+        match self.next {
+            Some(index) => match &self.entries[index] {
+                Entry::Vacant(generation, _) => Key {
+                    index,
+                    generation: *generation,
+                },
+                _ => unreachable!(),
+            },
+            _ => Key {
+                index: self.entries.len(),
+                generation: 0,
+            },
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: T) -> Key {
+        let key = self.vacant_key();
+
+        if key.index == self.entries.len() {
+            self.entries.push(Entry::Occupied(key.generation, value));
+        } else if let Entry::Vacant(_, next) = self.entries[key.index] {
+            self.next = next;
+            self.entries[key.index] = Entry::Occupied(key.generation, value);
+        }
+
+        self.len += 1;
+        key
+    }
+
+    pub(crate) fn remove(&mut self, key: Key) -> Option<T> {
+        match self.entries.get(key.index) {
+            Some(Entry::Occupied(generation, _)) if *generation == key.generation => {
+                let vacant = Entry::Vacant(key.generation.wrapping_add(1), self.next);
+                let entry = std::mem::replace(&mut self.entries[key.index], vacant);
+
+                self.next = Some(key.index);
+                self.len -= 1;
+
+                match entry {
+                    Entry::Occupied(_, value) => Some(value),
+                    _ => unreachable!(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        match self.entries.get_mut(key.index) {
+            Some(Entry::Occupied(generation, value)) if *generation == key.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Occupied(_, value) => Some(value),
+            _ => None,
+        })
+    }
+
+    // removes and returns all values. the generation of every key is advanced
+    // so that keys to the drained values can no longer remove anything
+    pub(crate) fn drain(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+
+        for index in 0..self.entries.len() {
+            if let Entry::Occupied(generation, _) = self.entries[index] {
+                let key = Key { index, generation };
+
+                if let Some(value) = self.remove(key) {
+                    values.push(value);
+                }
+            }
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn insert_should_add_value() {
+        // arrange
+        let mut slab = Slab::default();
+
+        // act
+        slab.insert(42);
+
+        // assert
+        assert_eq!(slab.len, 1);
+        assert_eq!(slab.values().collect::<Vec<_>>(), vec![&42]);
+    }
+
+    #[test]
+    fn remove_should_return_value() {
+        // arrange
+        let mut slab = Slab::default();
+        let key = slab.insert(42);
+
+        // act
+        let value = slab.remove(key);
+
+        // assert
+        assert_eq!(value, Some(42));
+        assert_eq!(slab.len, 0);
+    }
+
+    #[test]
+    fn get_mut_should_ignore_stale_key() {
+        // arrange
+        let mut slab = Slab::default();
+        let stale = slab.insert(1);
+
+        slab.remove(stale);
+        slab.insert(2);
+
+        // act
+        let value = slab.get_mut(stale);
+
+        // assert
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn insert_should_reuse_vacant_entry() {
+        // arrange
+        let mut slab = Slab::default();
+        let key = slab.insert(1);
+
+        slab.remove(key);
+
+        // act
+        slab.insert(2);
+
+        // assert
+        assert_eq!(slab.entries.len(), 1);
+    }
+
+    #[test]
+    fn remove_should_ignore_stale_key() {
+        // arrange
+        let mut slab = Slab::default();
+        let stale = slab.insert(1);
+
+        slab.remove(stale);
+        slab.insert(2);
+
+        // act
+        let value = slab.remove(stale);
+
+        // assert
+        assert_eq!(value, None);
+        assert_eq!(slab.len, 1);
+    }
+
+    #[test]
+    fn vacant_key_should_match_inserted_key() {
+        // arrange
+        let mut slab = Slab::default();
+        let key = slab.insert(1);
+
+        slab.insert(2);
+        slab.remove(key);
+
+        let expected = slab.vacant_key();
+
+        // act
+        let key = slab.insert(3);
+
+        // assert
+        assert_eq!(key, expected);
+    }
+
+    #[test]
+    fn drain_should_remove_all_values() {
+        // arrange
+        let mut slab = Slab::default();
+        let key = slab.insert(1);
+
+        slab.insert(2);
+
+        // act
+        let values = slab.drain();
+
+        // assert
+        assert_eq!(values, vec![1, 2]);
+        assert_eq!(slab.len, 0);
+        assert_eq!(slab.remove(key), None);
+    }
+}
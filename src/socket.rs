@@ -0,0 +1,353 @@
+use crate::{Callback, ChangeToken, DefaultChangeToken, Registration};
+use std::{
+    any::Any,
+    ffi::CString,
+    fs::{self, File, OpenOptions},
+    io::{Error, ErrorKind, Read, Result, Write},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{FileTypeExt, OpenOptionsExt},
+        io::{AsRawFd, RawFd},
+        net::{UnixDatagram, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+// the content of a notification is ignored
+const MESSAGE: &[u8] = b"\n";
+
+enum Source {
+    Socket(UnixDatagram),
+    Fifo(File),
+}
+
+impl Source {
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        match self {
+            Self::Socket(socket) => socket.recv(buffer),
+            Self::Fifo(fifo) => fifo.read(buffer),
+        }
+    }
+
+    // waits for the source to be readable or for the thread to be woken. the thread is woken
+    // when the other end of a socket pair is closed, which behaves the same on every platform
+    // and doesn't depend on the path of the source, which might have been removed
+    fn run(mut self, wake: UnixStream, token: Arc<DefaultChangeToken>) {
+        let mut buffer = [0u8; 64];
+        let mut fds = [
+            libc::pollfd {
+                fd: self.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: wake.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+
+        loop {
+            if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } < 0 {
+                if Error::last_os_error().kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+
+                break;
+            }
+
+            if fds[1].revents != 0 {
+                break;
+            }
+
+            if fds[0].revents & libc::POLLIN == 0 {
+                if fds[0].revents != 0 {
+                    break;
+                }
+
+                continue;
+            }
+
+            match self.receive(&mut buffer) {
+                Ok(0) => break,
+                // a callback that panics must not end the thread
+                Ok(_) => {
+                    token.try_notify().ok();
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                _ => break,
+            }
+        }
+    }
+}
+
+impl AsRawFd for Source {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Socket(socket) => socket.as_raw_fd(),
+            Self::Fifo(fifo) => fifo.as_raw_fd(),
+        }
+    }
+}
+
+/// Represents a [`ChangeToken`](crate::ChangeToken) that changes when a notification
+/// arrives on a Unix domain socket or FIFO.
+///
+/// # Remarks
+///
+/// Notifications are sent by other processes using [`notify_remote`]. Registered notifications always
+/// occur on another thread and the token changes every time a notification arrives. Notifications
+/// that arrive faster than the callbacks are invoked might be coalesced.
+pub struct SocketChangeToken {
+    inner: Arc<DefaultChangeToken>,
+    path: PathBuf,
+    socket: bool,
+    waker: Option<UnixStream>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SocketChangeToken {
+    /// Initializes a new change token that listens on a Unix domain datagram socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the socket to bind to
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the socket cannot be bound.
+    ///
+    /// # Remarks
+    ///
+    /// An existing socket at the path is replaced. The socket is removed when the token is dropped.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+            fs::remove_file(path)?;
+        }
+
+        let socket = UnixDatagram::bind(path)?;
+        Self::listen(path, true, Source::Socket(socket))
+    }
+
+    /// Initializes a new change token that listens on a FIFO.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The [path](std::path::Path) of the FIFO to listen on
+    ///
+    /// # Returns
+    ///
+    /// The new token or an [error](std::io::Error) if the FIFO cannot be opened.
+    ///
+    /// # Remarks
+    ///
+    /// The FIFO is created if it does not exist. The FIFO is not removed when the token is dropped.
+    pub fn fifo<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            let name = CString::new(path.as_os_str().as_bytes())?;
+
+            if unsafe { libc::mkfifo(name.as_ptr(), 0o600) } != 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+
+        // opening the FIFO for writing as well as reading means the open doesn't wait
+        // for a writer and reading never observes the end of the file between writers
+        let fifo = OpenOptions::new().read(true).write(true).open(path)?;
+
+        if !fifo.metadata()?.file_type().is_fifo() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a FIFO", path.display()),
+            ));
+        }
+
+        Self::listen(path, false, Source::Fifo(fifo))
+    }
+
+    fn listen(path: &Path, socket: bool, source: Source) -> Result<Self> {
+        let inner = Arc::new(DefaultChangeToken::new());
+        let token = inner.clone();
+        let (waker, wake) = UnixStream::pair()?;
+        let thread = thread::spawn(move || source.run(wake, token));
+
+        Ok(Self {
+            inner,
+            path: path.to_path_buf(),
+            socket,
+            waker: Some(waker),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl ChangeToken for SocketChangeToken {
+    fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.inner.register(callback, state)
+    }
+}
+
+impl Drop for SocketChangeToken {
+    fn drop(&mut self) {
+        // the thread is blocked polling the source so wake it by closing the other end of the pair
+        drop(self.waker.take());
+
+        // the token might be dropped by one of its own callbacks,
+        // which must not wait on the thread that is invoking it
+        if let Some(thread) = self.thread.take() {
+            if thread.thread().id() != thread::current().id() {
+                thread.join().ok();
+            }
+        }
+
+        if self.socket {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+/// Sends a notification to the [`SocketChangeToken`] listening on the specified path.
+///
+/// # Arguments
+///
+/// * `path` - The [path](std::path::Path) of the Unix domain socket or FIFO
+///
+/// # Returns
+///
+/// An [error](std::io::Error) if the notification cannot be sent, which includes
+/// when the path is not a socket or FIFO and when nothing is listening on it.
+///
+/// # Remarks
+///
+/// This function never blocks. If the listener has not yet received its previous
+/// notifications, the notification is coalesced with them.
+pub fn notify_remote<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let file_type = fs::metadata(path)?.file_type();
+    let result = if file_type.is_socket() {
+        let socket = UnixDatagram::unbound()?;
+        socket.set_nonblocking(true)?;
+        socket.send_to(MESSAGE, path).map(|_| ())
+    } else if file_type.is_fifo() {
+        // opening a FIFO that has no reader fails rather than waiting for one
+        OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?
+            .write_all(MESSAGE)
+    } else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a socket or FIFO", path.display()),
+        ));
+    };
+
+    match result {
+        Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::IntoReceiver;
+    use std::{env::temp_dir, process, sync::mpsc::channel, time::Duration};
+
+    fn path(name: &str) -> PathBuf {
+        temp_dir().join(format!("tokens.{}.{}", process::id(), name))
+    }
+
+    #[test]
+    fn socket_change_token_should_change_when_notified() {
+        // arrange
+        let path = path("1.sock");
+        let changed = SocketChangeToken::bind(&path).unwrap().into_receiver();
+
+        // act
+        notify_remote(&path).unwrap();
+
+        // assert
+        assert!(changed.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn fifo_change_token_should_change_when_notified() {
+        // arrange
+        let path = path("2.fifo");
+        let changed = SocketChangeToken::fifo(&path).unwrap().into_receiver();
+
+        // act
+        notify_remote(&path).unwrap();
+
+        // assert
+        assert!(changed.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        drop(changed);
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn dropped_socket_change_token_should_remove_socket() {
+        // arrange
+        let path = path("3.sock");
+        let token = SocketChangeToken::bind(&path).unwrap();
+
+        // act
+        drop(token);
+
+        // assert
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn dropped_change_token_should_not_wait_when_path_is_removed() {
+        // arrange
+        let socket = path("5.sock");
+        let fifo = path("6.fifo");
+        let tokens = vec![
+            SocketChangeToken::bind(&socket).unwrap(),
+            SocketChangeToken::fifo(&fifo).unwrap(),
+        ];
+        let (sender, receiver) = channel();
+
+        fs::remove_file(&socket).unwrap();
+        fs::remove_file(&fifo).unwrap();
+
+        // act
+        thread::spawn(move || {
+            drop(tokens);
+            sender.send(()).unwrap_or_default();
+        });
+
+        // assert
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(()));
+    }
+
+    #[test]
+    fn notify_remote_should_fail_when_nothing_is_listening() {
+        // arrange
+        let path = path("4.sock");
+
+        // act
+        let result = notify_remote(&path);
+
+        // assert
+        assert!(result.is_err());
+    }
+}
@@ -1,30 +1,193 @@
-use std::{any::Any, sync::Arc, ops::Deref};
+use crate::Key;
+use std::{
+    any::Any,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+};
 
 pub type Callback = Box<dyn Fn(Option<Arc<dyn Any>>) + Send + Sync>;
-type CallbackRef = Arc<dyn Fn(Option<Arc<dyn Any>>) + Send + Sync>;
+pub type VersionedCallback = Box<dyn Fn(u64, Option<Arc<dyn Any>>) + Send + Sync>;
+pub(crate) type CallbackRef = Arc<dyn Fn(Option<Arc<dyn Any>>) + Send + Sync>;
+
+// defines the behavior of the state shared between a registration and its change token
+pub(crate) trait Registered: Send + Sync {
+    fn is_active(&self) -> bool;
+    fn fired(&self) -> bool;
+    fn unregister(&self) -> bool;
+}
+
+// defines the behavior of the collection that owns registered callbacks
+pub(crate) trait Release: Send + Sync {
+    fn release(&self, key: Key);
+}
+
+// represents a registered callback. the registration holds the only strong reference
+// while the change token holds a weak reference, which ensures that a change token
+// never keeps a callback alive longer than its registration. a slot is active until
+// it's unregistered and attached until the change token will never invoke it again.
+// a detached slot may still be invoked by a notification that is already in flight
+pub(crate) struct Slot<F: ?Sized> {
+    active: AtomicBool,
+    attached: AtomicBool,
+    fired: AtomicBool,
+    once: bool,
+    owner: Option<(Weak<dyn Release>, Key)>,
+    callback: Box<F>,
+}
+
+impl<F: ?Sized> Slot<F> {
+    pub(crate) fn new(callback: Box<F>) -> Self {
+        Self {
+            active: AtomicBool::new(true),
+            attached: AtomicBool::new(true),
+            fired: AtomicBool::default(),
+            once: false,
+            owner: None,
+            callback,
+        }
+    }
+
+    // creates a slot that is released from its owner as soon as it's unregistered.
+    // a slot that fires once is unregistered as soon as it fires
+    pub(crate) fn owned(callback: Box<F>, owner: Weak<dyn Release>, key: Key, once: bool) -> Self {
+        Self {
+            once,
+            owner: Some((owner, key)),
+            ..Self::new(callback)
+        }
+    }
+
+    // gets the callback if it should still be invoked and records that it fired
+    pub(crate) fn fire(&self) -> Option<&F> {
+        if self.once {
+            // only the notification that unregisters the slot can invoke it
+            if !self.deactivate() {
+                return None;
+            }
+        } else if !self.active.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        self.fired.store(true, Ordering::SeqCst);
+        Some(&self.callback)
+    }
+
+    // deactivates the slot and releases it from its owner, if it was active
+    fn deactivate(&self) -> bool {
+        if self.active.swap(false, Ordering::SeqCst) {
+            if let Some((owner, key)) = &self.owner {
+                if let Some(owner) = owner.upgrade() {
+                    owner.release(*key);
+                }
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn detach(&self) {
+        self.attached.store(false, Ordering::SeqCst)
+    }
+}
+
+impl<F: ?Sized + Send + Sync> Registered for Slot<F> {
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst) && self.attached.load(Ordering::SeqCst)
+    }
+
+    fn fired(&self) -> bool {
+        self.fired.load(Ordering::SeqCst)
+    }
+
+    fn unregister(&self) -> bool {
+        // only the caller that deactivates the slot observes that it was still registered,
+        // which is never the case once a notification has claimed a slot that fires once
+        self.deactivate() && self.attached.load(Ordering::SeqCst)
+    }
+}
+
+// represents a callback that is retained without any knowledge of the change token
+struct Retained(CallbackRef);
+
+impl Registered for Retained {
+    fn is_active(&self) -> bool {
+        Arc::weak_count(&self.0) > 0
+    }
+
+    fn fired(&self) -> bool {
+        false
+    }
+
+    fn unregister(&self) -> bool {
+        self.is_active()
+    }
+}
 
 /// Represents a [`ChangeToken`](crate::ChangeToken) registration.
 ///
 /// # Remarks
 ///
 /// When the registration is dropped, the underlying callback is unregistered.
-pub struct Registration(CallbackRef);
+#[derive(Default)]
+pub struct Registration(Option<Arc<dyn Registered>>);
 
 impl Registration {
     /// Initializes a new change token registration.
     pub fn new(callback: CallbackRef) -> Self {
-        Self(callback)
+        Self(Some(Arc::new(Retained(callback))))
+    }
+
+    pub(crate) fn slot<F: ?Sized + Send + Sync + 'static>(slot: Arc<Slot<F>>) -> Self {
+        Self(Some(slot))
     }
 
     /// Initializes a new, empty change token registration.
     pub fn none() -> Self {
         Self::default()
     }
+
+    /// Gets a value indicating whether the change token still holds the registered callback.
+    ///
+    /// # Remarks
+    ///
+    /// A registration becomes inactive when it is unregistered, when the change token
+    /// is dropped, or when the change token will never invoke the callback again.
+    pub fn is_active(&self) -> bool {
+        self.0.as_ref().map(|r| r.is_active()).unwrap_or_default()
+    }
+
+    /// Gets a value indicating whether the registered callback has been invoked at least once.
+    pub fn fired(&self) -> bool {
+        self.0.as_ref().map(|r| r.fired()).unwrap_or_default()
+    }
+
+    /// Unregisters the registered callback.
+    ///
+    /// # Returns
+    ///
+    /// True if the callback was still registered; otherwise, false.
+    ///
+    /// # Remarks
+    ///
+    /// Once this function returns, the callback will not be invoked by any subsequent change.
+    /// This is equivalent to dropping the registration, but indicates the state of the callback.
+    pub fn unregister(self) -> bool {
+        self.0.as_ref().map(|r| r.unregister()).unwrap_or_default()
+    }
 }
 
-impl Default for Registration {
-    fn default() -> Self {
-        Self(Arc::new(|_| {}))
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // the change token may have already captured the callback for an in-flight
+        // notification. deactivating ensures it won't be invoked after it's dropped
+        if let Some(registered) = &self.0 {
+            registered.unregister();
+        }
     }
 }
 
@@ -45,6 +208,17 @@ pub trait ChangeToken: Send + Sync {
         false
     }
 
+    /// Gets the current version of the token.
+    ///
+    /// # Remarks
+    ///
+    /// The version is a monotonically increasing sequence number that is incremented each
+    /// time the token changes. Comparing the versions from two observations indicates how
+    /// many changes occurred between them. Tokens that do not track changes always return zero.
+    fn version(&self) -> u64 {
+        0
+    }
+
     /// Registers for a callback that will be invoked when the token has changed.
     ///
     /// # Arguments
@@ -57,6 +231,29 @@ pub trait ChangeToken: Send + Sync {
     /// An opaque change token [registration](Registration). When it
     /// is dropped, the callback function is unregistered.
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration;
+
+    /// Registers for a callback that will be invoked when the token has changed or
+    /// invokes the callback immediately if the token has already changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke
+    /// * `state` - The optional state provided to the callback, if any
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// A token that changes at most once will never invoke a callback registered after it has
+    /// changed. Such a token invokes the callback synchronously on the calling thread instead and
+    /// guarantees that the callback is invoked exactly once, even if the change occurs while the
+    /// callback is being registered. The default implementation only registers the callback.
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.register(callback, state)
+    }
 }
 
 // this allows Box<dyn ChangeToken> to be used for T: ChangeToken
@@ -69,7 +266,15 @@ impl ChangeToken for Box<dyn ChangeToken> {
         self.deref().must_poll()
     }
 
+    fn version(&self) -> u64 {
+        self.deref().version()
+    }
+
     fn register(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
         self.deref().register(callback, state)
     }
+
+    fn register_or_invoke(&self, callback: Callback, state: Option<Arc<dyn Any>>) -> Registration {
+        self.deref().register_or_invoke(callback, state)
+    }
 }
\ No newline at end of file
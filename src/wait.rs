@@ -0,0 +1,505 @@
+use crate::{polling::POLL_INTERVAL, sync::MutexExt, ChangeToken, Registration};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, PoisonError, Weak},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+// records the index of the first token that changed
+#[derive(Default)]
+struct Signal {
+    changed: Mutex<Option<usize>>,
+    event: Condvar,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Signal {
+    fn set(&self, index: usize) {
+        self.changed.lock_or_recover().get_or_insert(index);
+        self.event.notify_all();
+        self.wake();
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = &*self.waker.lock_or_recover() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+// only a token that must be polled is checked using changed. a multi-shot token is only
+// changed while its callbacks are being invoked, which would end every wait that starts
+// during a single notification. a token that changes at most once and has already
+// changed or a latching token with an unacknowledged change invokes its callback as
+// soon as it's registered instead
+fn first_polled<T: ChangeToken + ?Sized>(tokens: &[&T]) -> Option<usize> {
+    tokens.iter().position(|t| t.must_poll() && t.changed())
+}
+
+fn versions<T: ChangeToken + ?Sized>(tokens: &[&T]) -> Vec<u64> {
+    tokens.iter().map(|t| t.version()).collect()
+}
+
+// registers for a change on every token that doesn't require polling and returns the
+// registrations along with a value indicating whether any token must be polled. a
+// multi-shot token that changed after its version was observed signals immediately
+fn register<T: ChangeToken + ?Sized>(
+    tokens: &[&T],
+    versions: &[u64],
+    signal: &Arc<Signal>,
+) -> (Vec<Registration>, bool) {
+    let mut registrations = Vec::with_capacity(tokens.len());
+    let mut polled = false;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if token.must_poll() {
+            polled = true;
+            continue;
+        }
+
+        let handler = signal.clone();
+
+        registrations.push(token.register_or_invoke(Box::new(move |_| handler.set(index)), None));
+
+        if token.version() != versions[index] {
+            signal.set(index);
+        }
+    }
+
+    (registrations, polled)
+}
+
+fn wait_any_until<T: ChangeToken + ?Sized>(
+    tokens: &[&T],
+    deadline: Option<Instant>,
+) -> Option<usize> {
+    let versions = versions(tokens);
+
+    if let Some(index) = first_polled(tokens) {
+        return Some(index);
+    }
+
+    let signal = Arc::new(Signal::default());
+    let (_registrations, polled) = register(tokens, &versions, &signal);
+    let mut changed = signal.changed.lock_or_recover();
+
+    loop {
+        if let Some(index) = *changed {
+            return Some(index);
+        }
+
+        let mut timeout = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => Some(remaining),
+                _ => return None,
+            },
+            _ => None,
+        };
+
+        if polled {
+            timeout = Some(timeout.map_or(POLL_INTERVAL, |t| t.min(POLL_INTERVAL)));
+        }
+
+        changed = match timeout {
+            Some(timeout) => {
+                signal
+                    .event
+                    .wait_timeout(changed, timeout)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            _ => signal
+                .event
+                .wait(changed)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+
+        if polled && changed.is_none() {
+            if let Some(index) = first_polled(tokens) {
+                return Some(index);
+            }
+        }
+    }
+}
+
+/// Provides blocking functions to wait for a [`ChangeToken`](crate::ChangeToken) to change.
+///
+/// # Remarks
+///
+/// This trait is implemented for every [`ChangeToken`](crate::ChangeToken). The functions return after
+/// the next change that occurs once they are called, which is determined by the [version](crate::ChangeToken::version)
+/// of the token. The functions return immediately if a token that changes at most once has already changed,
+/// a [`LatchingChangeToken`](crate::LatchingChangeToken) has an unacknowledged change, or a token that
+/// [must be polled](crate::ChangeToken::must_poll) is [changed](crate::ChangeToken::changed). A token
+/// that must be polled is checked for changes every 100 milliseconds.
+pub trait Wait: ChangeToken {
+    /// Blocks the current thread until the token changes.
+    fn wait(&self) {
+        wait_any_until(&[self], None);
+    }
+
+    /// Blocks the current thread until the token changes or the specified timeout elapses.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum amount of time to wait
+    ///
+    /// # Returns
+    ///
+    /// True if the token changed; otherwise, false if the timeout elapsed.
+    fn wait_timeout(&self, timeout: Duration) -> bool {
+        wait_any_until(&[self], Instant::now().checked_add(timeout)).is_some()
+    }
+}
+
+impl<T: ChangeToken + ?Sized> Wait for T {}
+
+/// Blocks the current thread until any of the specified tokens change.
+///
+/// # Arguments
+///
+/// * `tokens` - The [change tokens](crate::ChangeToken) to wait on
+///
+/// # Returns
+///
+/// The index of the first token that changed.
+///
+/// # Remarks
+///
+/// If more than one token has already changed, the lowest index is returned.
+/// A token that [must be polled](crate::ChangeToken::must_poll) is checked
+/// for changes every 100 milliseconds.
+pub fn wait_any(tokens: &[&dyn ChangeToken]) -> usize {
+    wait_any_until(tokens, None).expect("a change without a deadline")
+}
+
+/// Blocks the current thread until any of the specified tokens change or the specified timeout elapses.
+///
+/// # Arguments
+///
+/// * `tokens` - The [change tokens](crate::ChangeToken) to wait on
+/// * `timeout` - The maximum amount of time to wait
+///
+/// # Returns
+///
+/// The index of the first token that changed or `None` if the timeout elapsed.
+pub fn wait_any_timeout(tokens: &[&dyn ChangeToken], timeout: Duration) -> Option<usize> {
+    wait_any_until(tokens, Instant::now().checked_add(timeout))
+}
+
+/// Waits asynchronously until any of the specified tokens change.
+///
+/// # Arguments
+///
+/// * `tokens` - The [change tokens](crate::ChangeToken) to wait on
+///
+/// # Returns
+///
+/// A [future](WaitAny) that resolves to the index of the first token that changed.
+///
+/// # Remarks
+///
+/// A change that occurs after this function is called is observed even if the future has
+/// not been polled yet. The future does not depend on any particular async runtime. If any token
+/// [must be polled](crate::ChangeToken::must_poll), a helper thread wakes the
+/// future every 100 milliseconds until it completes or is dropped.
+pub fn wait_any_async<'a>(tokens: &'a [&'a dyn ChangeToken]) -> WaitAny<'a> {
+    WaitAny::since(tokens, versions(tokens))
+}
+
+/// Represents a [future](std::future::Future) that resolves when any of several change tokens change.
+pub struct WaitAny<'a> {
+    tokens: &'a [&'a dyn ChangeToken],
+    versions: Vec<u64>,
+    signal: Arc<Signal>,
+    registrations: Option<Vec<Registration>>,
+    ticks: bool,
+}
+
+impl<'a> WaitAny<'a> {
+    // creates a future that resolves when any token changes after the specified versions were observed
+    pub(crate) fn since(tokens: &'a [&'a dyn ChangeToken], versions: Vec<u64>) -> Self {
+        Self {
+            tokens,
+            versions,
+            signal: Arc::default(),
+            registrations: None,
+            ticks: true,
+        }
+    }
+
+    // the caller polls the future periodically when a token must be polled, so no helper thread is started
+    pub(crate) fn without_ticks(mut self) -> Self {
+        self.ticks = false;
+        self
+    }
+
+    fn tick(signal: Weak<Signal>) {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            match signal.upgrade() {
+                Some(signal) => signal.wake(),
+                _ => break,
+            }
+        }
+    }
+}
+
+impl Future for WaitAny<'_> {
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // the waker must be stored before checking for a change so that
+        // a change which occurs immediately afterward still wakes the task
+        *this.signal.waker.lock_or_recover() = Some(cx.waker().clone());
+
+        if let Some(index) = *this.signal.changed.lock_or_recover() {
+            return Poll::Ready(index);
+        }
+
+        if let Some(index) = first_polled(this.tokens) {
+            return Poll::Ready(index);
+        }
+
+        if this.registrations.is_none() {
+            let (registrations, polled) = register(this.tokens, &this.versions, &this.signal);
+
+            this.registrations = Some(registrations);
+
+            if polled && this.ticks {
+                let signal = Arc::downgrade(&this.signal);
+                thread::spawn(move || Self::tick(signal));
+            }
+
+            if let Some(index) = *this.signal.changed.lock_or_recover() {
+                return Poll::Ready(index);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        test::block_on, Callback, DefaultChangeToken, LatchingChangeToken, NeverChangeToken,
+        Registration, SharedChangeToken, SingleChangeToken,
+    };
+    use std::any::Any;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::channel;
+
+    #[derive(Default)]
+    struct PolledChangeToken(AtomicBool);
+
+    impl ChangeToken for PolledChangeToken {
+        fn changed(&self) -> bool {
+            self.0.load(Ordering::SeqCst)
+        }
+
+        fn must_poll(&self) -> bool {
+            true
+        }
+
+        fn register(&self, _callback: Callback, _state: Option<Arc<dyn Any>>) -> Registration {
+            Registration::none()
+        }
+    }
+
+    #[test]
+    fn wait_should_return_immediately_when_token_has_changed() {
+        // arrange
+        let token = SingleChangeToken::new();
+
+        token.notify();
+
+        // act
+        token.wait();
+
+        // assert
+        assert!(token.changed());
+    }
+
+    #[test]
+    fn wait_timeout_should_return_true_when_latching_token_is_changed() {
+        // arrange
+        let token = LatchingChangeToken::new();
+
+        token.notify();
+
+        // act
+        let changed = token.wait_timeout(Duration::from_millis(10));
+
+        // assert
+        assert!(changed);
+    }
+
+    #[test]
+    fn wait_timeout_should_return_false_when_token_does_not_change() {
+        // arrange
+        let token = NeverChangeToken::new();
+
+        // act
+        let changed = token.wait_timeout(Duration::from_millis(10));
+
+        // assert
+        assert!(!changed);
+    }
+
+    #[test]
+    fn wait_timeout_should_return_true_when_token_changes() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            producer.notify();
+        });
+
+        // act
+        let changed = token.wait_timeout(Duration::from_secs(5));
+
+        // assert
+        handle.join().unwrap();
+        assert!(changed);
+    }
+
+    #[test]
+    fn wait_timeout_should_not_observe_change_that_is_in_flight() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (started, invoked) = channel();
+        let _registration = token.register(
+            Box::new(move |_| {
+                started.send(()).unwrap_or_default();
+                thread::sleep(Duration::from_millis(100));
+            }),
+            None,
+        );
+        let handle = thread::spawn(move || producer.notify());
+
+        invoked.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // act
+        let changed = token.wait_timeout(Duration::from_millis(10));
+
+        // assert
+        handle.join().unwrap();
+        assert!(!changed);
+    }
+
+    #[test]
+    fn wait_timeout_should_poll_token_that_must_be_polled() {
+        // arrange
+        let token = Arc::new(PolledChangeToken::default());
+        let producer = token.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            producer.0.store(true, Ordering::SeqCst);
+        });
+
+        // act
+        let changed = token.wait_timeout(Duration::from_secs(5));
+
+        // assert
+        handle.join().unwrap();
+        assert!(changed);
+    }
+
+    #[test]
+    fn wait_any_should_return_index_of_changed_token() {
+        // arrange
+        let never = NeverChangeToken::new();
+        let config = DefaultChangeToken::new();
+        let shutdown = SharedChangeToken::<SingleChangeToken>::default();
+        let producer = shutdown.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            producer.notify();
+        });
+
+        // act
+        let index = wait_any(&[&never, &config, &shutdown]);
+
+        // assert
+        handle.join().unwrap();
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn wait_any_should_return_lowest_index_when_tokens_have_changed() {
+        // arrange
+        let first = SingleChangeToken::new();
+        let second = SingleChangeToken::new();
+
+        first.notify();
+        second.notify();
+
+        // act
+        let index = wait_any(&[&second, &first]);
+
+        // assert
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn wait_any_timeout_should_return_none_when_no_token_changes() {
+        // arrange
+        let first = DefaultChangeToken::new();
+        let second = NeverChangeToken::new();
+
+        // act
+        let index = wait_any_timeout(&[&first, &second], Duration::from_millis(10));
+
+        // assert
+        assert_eq!(index, None);
+    }
+
+    #[test]
+    fn wait_any_async_should_resolve_to_index_of_changed_token() {
+        // arrange
+        let config = DefaultChangeToken::new();
+        let shutdown = SharedChangeToken::<SingleChangeToken>::default();
+        let producer = shutdown.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            producer.notify();
+        });
+
+        // act
+        let index = block_on(wait_any_async(&[&config, &shutdown]));
+
+        // assert
+        handle.join().unwrap();
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn wait_any_async_should_poll_token_that_must_be_polled() {
+        // arrange
+        let config = DefaultChangeToken::new();
+        let polled = Arc::new(PolledChangeToken::default());
+        let producer = polled.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            producer.0.store(true, Ordering::SeqCst);
+        });
+
+        // act
+        let index = block_on(wait_any_async(&[&config, &*polled]));
+
+        // assert
+        handle.join().unwrap();
+        assert_eq!(index, 1);
+    }
+}
@@ -0,0 +1,538 @@
+use crate::{
+    on_change,
+    sync::{MutexExt, RwLockExt},
+    ChangeToken, Key, Registration, Release, Slab, Slot, Subscription,
+};
+use arc_swap::ArcSwap;
+use std::{
+    collections::VecDeque,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock, Weak,
+    },
+};
+
+type Subscriber<T> = dyn Fn(Arc<T>, Arc<T>) + Send + Sync;
+
+struct Entry<T> {
+    slot: Weak<Slot<Subscriber<T>>>,
+    sequence: u64,
+}
+
+// the reloads that have not been delivered to subscribers, in the order the values were stored
+struct Pending<T> {
+    reloads: VecDeque<(Arc<T>, Arc<T>)>,
+    delivering: bool,
+}
+
+impl<T> Default for Pending<T> {
+    fn default() -> Self {
+        Self {
+            reloads: VecDeque::new(),
+            delivering: false,
+        }
+    }
+}
+
+// subscribers release their entry as soon as they are dropped, which keeps
+// subscribing and unsubscribing O(1) regardless of churn
+struct Cell<T> {
+    loader: Box<dyn Fn() -> Option<T> + Send + Sync>,
+    value: ArcSwap<T>,
+    loading: Mutex<()>,
+    pending: Mutex<Pending<T>>,
+    callbacks: RwLock<Slab<Entry<T>>>,
+    sequence: AtomicU64,
+}
+
+impl<T: Send + Sync + 'static> Cell<T> {
+    fn reload(&self) {
+        {
+            // loads are serialized so that a value is never replaced by a value that was
+            // loaded before it. the lock guards no data and is released before delivery
+            let _loading = self.loading.lock_or_recover();
+
+            // a loader that fails has already reported the error so
+            // the last good value is retained
+            let new = match (self.loader)() {
+                Some(value) => Arc::new(value),
+                _ => return,
+            };
+            let old = self.value.swap(new.clone());
+            let mut pending = self.pending.lock_or_recover();
+
+            pending.reloads.push_back((old, new));
+
+            // the thread that is already delivering will deliver this reload in order
+            if pending.delivering {
+                return;
+            }
+
+            pending.delivering = true;
+        }
+
+        self.deliver();
+    }
+
+    // only one thread delivers at a time, which notifies subscribers in the order the
+    // values were stored without holding a lock. a subscriber that panics does not
+    // prevent the remaining subscribers from being notified
+    fn deliver(&self) {
+        let mut panics = Vec::new();
+
+        loop {
+            let (old, new) = {
+                let mut pending = self.pending.lock_or_recover();
+
+                match pending.reloads.pop_front() {
+                    Some(reload) => reload,
+                    _ => {
+                        pending.delivering = false;
+                        break;
+                    }
+                }
+            };
+
+            for slot in self.subscribers() {
+                if let Some(callback) = slot.fire() {
+                    let result =
+                        catch_unwind(AssertUnwindSafe(|| callback(old.clone(), new.clone())));
+
+                    if let Err(panic) = result {
+                        panics.push(panic);
+                    }
+                }
+            }
+        }
+
+        if !panics.is_empty() {
+            resume_unwind(panics.remove(0))
+        }
+    }
+
+    fn subscribers(&self) -> Vec<Arc<Slot<Subscriber<T>>>> {
+        let mut subscribers: Vec<_> = self
+            .callbacks
+            .read_or_recover()
+            .values()
+            .filter_map(|e| e.slot.upgrade().map(|slot| (e.sequence, slot)))
+            .collect();
+
+        subscribers.sort_unstable_by_key(|(sequence, _)| *sequence);
+        subscribers.into_iter().map(|(_, slot)| slot).collect()
+    }
+}
+
+impl<T: Send + Sync> Release for Cell<T> {
+    fn release(&self, key: Key) {
+        // the entry is dropped after the write-lock is released
+        let entry = self.callbacks.write_or_recover().remove(key);
+        drop(entry);
+    }
+}
+
+impl<T> Drop for Cell<T> {
+    fn drop(&mut self) {
+        for entry in self.callbacks.get_mut_or_recover().values() {
+            if let Some(slot) = entry.slot.upgrade() {
+                slot.detach();
+            }
+        }
+    }
+}
+
+/// Represents a value that is reloaded whenever its source changes.
+///
+/// # Remarks
+///
+/// The current value is held as an [`Arc`](std::sync::Arc) which is atomically swapped
+/// when the value is reloaded. Values previously returned by [`get`](Watched::get) are
+/// unaffected by a reload.
+pub struct Watched<T: Send + Sync + 'static> {
+    cell: Arc<Cell<T>>,
+    _subscription: Box<dyn Subscription + Send + Sync>,
+}
+
+impl<T: Send + Sync + 'static> Watched<T> {
+    /// Initializes a new watched value.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - The function that loads the value
+    /// * `producer` - The function that produces the [change token](crate::ChangeToken) for the value's source
+    ///
+    /// # Remarks
+    ///
+    /// The value is loaded immediately and then reloaded every time the change token
+    /// produced by `producer` changes.
+    pub fn new<TToken, TLoader, TProducer>(loader: TLoader, producer: TProducer) -> Self
+    where
+        TToken: ChangeToken + 'static,
+        TLoader: Fn() -> T + Send + Sync + 'static,
+        TProducer: Fn() -> TToken + Send + Sync + 'static,
+    {
+        let value = loader();
+        Self::create(value, Box::new(move || Some(loader())), producer)
+    }
+
+    /// Initializes a new watched value using a loader that can fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - The function that loads the value
+    /// * `producer` - The function that produces the [change token](crate::ChangeToken) for the value's source
+    /// * `on_error` - The function that is called when reloading the value fails
+    ///
+    /// # Remarks
+    ///
+    /// If the initial load fails, the error is returned. If a subsequent reload fails, the error
+    /// is provided to `on_error` and the last good value is retained. The value continues to be
+    /// reloaded when the source changes.
+    pub fn try_new<TToken, TLoader, TProducer, TError, THandler>(
+        loader: TLoader,
+        producer: TProducer,
+        on_error: THandler,
+    ) -> Result<Self, TError>
+    where
+        TToken: ChangeToken + 'static,
+        TLoader: Fn() -> Result<T, TError> + Send + Sync + 'static,
+        TProducer: Fn() -> TToken + Send + Sync + 'static,
+        THandler: Fn(TError) + Send + Sync + 'static,
+    {
+        let value = loader()?;
+        let loader = move || match loader() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                on_error(error);
+                None
+            }
+        };
+
+        Ok(Self::create(value, Box::new(loader), producer))
+    }
+
+    fn create<TToken, TProducer>(
+        value: T,
+        loader: Box<dyn Fn() -> Option<T> + Send + Sync>,
+        producer: TProducer,
+    ) -> Self
+    where
+        TToken: ChangeToken + 'static,
+        TProducer: Fn() -> TToken + Send + Sync + 'static,
+    {
+        let cell = Arc::new(Cell {
+            value: ArcSwap::from_pointee(value),
+            loading: Mutex::default(),
+            pending: Mutex::default(),
+            loader,
+            callbacks: Default::default(),
+            sequence: AtomicU64::default(),
+        });
+        let subscription = on_change(
+            producer,
+            |state: Option<Arc<Weak<Cell<T>>>>| {
+                if let Some(cell) = state.unwrap().upgrade() {
+                    cell.reload();
+                }
+            },
+            Some(Arc::new(Arc::downgrade(&cell))),
+        );
+
+        Self {
+            cell,
+            _subscription: Box::new(subscription),
+        }
+    }
+
+    /// Gets the current value.
+    pub fn get(&self) -> Arc<T> {
+        self.cell.value.load_full()
+    }
+
+    /// Reloads the current value, regardless of whether its source has changed.
+    ///
+    /// # Remarks
+    ///
+    /// Only one value is loaded at a time. If the value is already being loaded, this function
+    /// blocks until that load has completed. If subscribers are being notified of a previous
+    /// reload, this function returns without waiting and they are notified of this reload after
+    /// the reloads before it. A subscriber that panics does not prevent the remaining subscribers
+    /// from being notified; the first panic is resumed after every subscriber has been notified.
+    pub fn reload(&self) {
+        self.cell.reload()
+    }
+
+    /// Registers for a callback that will be invoked when the value has been reloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback to invoke with the old and new values, respectively
+    ///
+    /// # Returns
+    ///
+    /// An opaque change token [registration](crate::Registration). When it
+    /// is dropped, the callback function is unregistered.
+    ///
+    /// # Remarks
+    ///
+    /// The callback is invoked for each reload in the order the values were reloaded. Callbacks
+    /// are invoked in the order they were registered. A callback that [reloads](Watched::reload)
+    /// the value is notified of that reload after it returns.
+    pub fn subscribe<F>(&self, callback: F) -> Registration
+    where
+        F: Fn(Arc<T>, Arc<T>) + Send + Sync + 'static,
+    {
+        let owner: Arc<dyn Release> = self.cell.clone();
+        let callback: Box<Subscriber<T>> = Box::new(callback);
+        let mut callbacks = self.cell.callbacks.write_or_recover();
+        let key = callbacks.vacant_key();
+        let slot = Arc::new(Slot::owned(callback, Arc::downgrade(&owner), key, false));
+
+        callbacks.insert(Entry {
+            slot: Arc::downgrade(&slot),
+            sequence: self.cell.sequence.fetch_add(1, Ordering::Relaxed),
+        });
+        Registration::slot(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn() -> usize + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::default());
+        let loader = count.clone();
+        (count, move || loader.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    #[test]
+    fn get_should_return_initial_value() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (_, loader) = counter();
+        let watched = Watched::new(loader, move || producer.clone());
+
+        // act
+        let value = watched.get();
+
+        // assert
+        assert_eq!(*value, 1);
+    }
+
+    #[test]
+    fn get_should_return_new_value_when_source_changes() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (_, loader) = counter();
+        let watched = Watched::new(loader, move || producer.clone());
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(*watched.get(), 2);
+    }
+
+    #[test]
+    fn subscriber_should_receive_old_and_new_values() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (_, loader) = counter();
+        let watched = Watched::new(loader, move || producer.clone());
+        let values = Arc::new(Mutex::new(Vec::new()));
+        let sink = values.clone();
+        let _registration = watched.subscribe(move |old, new| {
+            sink.lock().unwrap().push((*old, *new));
+        });
+
+        // act
+        token.notify();
+        token.notify();
+
+        // assert
+        assert_eq!(*values.lock().unwrap(), vec![(1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn subscriber_should_receive_values_in_order_when_reloads_race() {
+        // arrange
+        let (_, loader) = counter();
+        let watched = Arc::new(Watched::new(loader, NeverChangeToken::new));
+        let values = Arc::new(Mutex::new(Vec::new()));
+        let sink = values.clone();
+        let _registration = watched.subscribe(move |old, new| {
+            sink.lock().unwrap().push((*old, *new));
+        });
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let watched = watched.clone();
+                std::thread::spawn(move || (0..25).for_each(|_| watched.reload()))
+            })
+            .collect();
+
+        // act
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        // assert
+        let values = values.lock().unwrap();
+        assert_eq!(values.len(), 100);
+        assert!(values.iter().all(|(old, new)| old + 1 == *new));
+        assert!(values.windows(2).all(|w| w[0].1 == w[1].0));
+        assert_eq!(*watched.get(), 101);
+    }
+
+    #[test]
+    fn subscriber_should_be_notified_when_another_subscriber_panics() {
+        // arrange
+        let (_, loader) = counter();
+        let watched = Watched::new(loader, NeverChangeToken::new);
+        let notified = Arc::new(AtomicUsize::default());
+        let count = notified.clone();
+        let _first = watched.subscribe(|_, _| panic!("expected"));
+        let _second = watched.subscribe(move |_, _| {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // act
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| watched.reload()));
+
+        // assert
+        assert!(result.is_err());
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+        assert_eq!(*watched.get(), 2);
+    }
+
+    #[test]
+    fn subscriber_should_be_notified_of_reload_it_requests() {
+        // arrange
+        let (_, loader) = counter();
+        let watched = Arc::new(Watched::new(loader, NeverChangeToken::new));
+        let reloader = Arc::downgrade(&watched);
+        let values = Arc::new(Mutex::new(Vec::new()));
+        let sink = values.clone();
+        let _registration = watched.subscribe(move |old, new| {
+            sink.lock().unwrap().push((*old, *new));
+
+            if *new == 2 {
+                reloader.upgrade().unwrap().reload();
+            }
+        });
+
+        // act
+        watched.reload();
+
+        // assert
+        assert_eq!(*values.lock().unwrap(), vec![(1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn subscriber_should_not_be_notified_after_registration_is_dropped() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (_, loader) = counter();
+        let watched = Watched::new(loader, move || producer.clone());
+        let notified = Arc::new(AtomicUsize::default());
+        let count = notified.clone();
+        let registration = watched.subscribe(move |_, _| {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // act
+        drop(registration);
+        token.notify();
+
+        // assert
+        assert_eq!(notified.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn try_new_should_return_error_when_initial_load_fails() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+
+        // act
+        let result = Watched::<usize>::try_new(|| Err("failed"), move || producer.clone(), |_| {});
+
+        // assert
+        assert_eq!(result.err(), Some("failed"));
+    }
+
+    #[test]
+    fn failed_reload_should_report_error_and_retain_last_good_value() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (count, loader) = counter();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let sink = errors.clone();
+        let watched = Watched::try_new(
+            move || match loader() {
+                2 => Err("invalid"),
+                value => Ok(value),
+            },
+            move || producer.clone(),
+            move |error| sink.lock().unwrap().push(error),
+        )
+        .unwrap();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(*watched.get(), 1);
+        assert_eq!(*errors.lock().unwrap(), vec!["invalid"]);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn value_should_reload_after_failed_reload() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (_, loader) = counter();
+        let watched = Watched::try_new(
+            move || match loader() {
+                2 => Err("invalid"),
+                value => Ok(value),
+            },
+            move || producer.clone(),
+            |_| {},
+        )
+        .unwrap();
+
+        token.notify();
+
+        // act
+        token.notify();
+
+        // assert
+        assert_eq!(*watched.get(), 3);
+    }
+
+    #[test]
+    fn source_should_not_be_reloaded_after_watched_value_is_dropped() {
+        // arrange
+        let token = SharedChangeToken::<DefaultChangeToken>::default();
+        let producer = token.clone();
+        let (count, loader) = counter();
+        let watched = Watched::new(loader, move || producer.clone());
+
+        // act
+        drop(watched);
+        token.notify();
+
+        // assert
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}